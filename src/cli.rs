@@ -0,0 +1,113 @@
+//! Command line interface of the `aima_8slide` binary
+//! The arguments are parsed by hand to keep the crate free of dependencies;
+//! every subcommand writes into the given output, so it can be tested without a terminal.
+
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+
+pub mod args;
+pub mod batch;
+pub mod bench;
+pub mod generate;
+pub mod output;
+pub mod play;
+pub mod render;
+pub mod solve;
+pub mod verify;
+
+use crate::cli::args::{Command, ALGORITHMS};
+
+pub const USAGE: &str = "
+Usage:
+    aima_8slide solve BOARD [--algorithm NAME] [flags]
+    aima_8slide batch [FILE] [flags]
+    aima_8slide check BOARD [--goal BOARD]
+    aima_8slide verify BOARD MOVES [--goal BOARD] [--show]
+    aima_8slide generate [--count N] [--walk N | --distance N] [--seed N] [--goal BOARD]
+    aima_8slide bench [--algorithms LIST] [--heuristics LIST] [--depths LIST] [flags]
+    aima_8slide play [BOARD] [--goal BOARD] [--seed N] [--delay SECONDS]
+    aima_8slide BOARD [ALGORITHM] [--dot FILE]
+
+Examples:
+    aima_8slide solve 1,2,3,4,5,0,7,8,6
+    aima_8slide solve 1,2,3,7,4,5,0,8,6 --algorithm astar --heuristic misplaced
+    aima_8slide check 8,6,7,2,5,4,3,0,1
+    aima_8slide verify 1,2,3,4,0,5,7,8,6 RD --show
+    aima_8slide batch boards.txt --algorithm astar --timeout 2
+    aima_8slide generate --count 20 --distance 18 | aima_8slide batch --algorithm astar
+
+Flags of solve:
+    --algorithm NAME - search algorithm, breadth_first by default
+    --heuristic NAME - manhattan (default) or misplaced, used by the informed and local searches
+    --depth N - depth limit of depth_limited, 10 by default
+    --goal BOARD - goal board, 1,2,3,4,5,6,7,8,0 by default
+    --max-nodes N - gives up after expanding N nodes
+    --timeout SECONDS - gives up after the time, fractions like 0.5 are allowed;
+        local searches and genetic stop on their own and take neither limit
+    --format NAME - text (default), json, csv or compact moves like URRD
+    --dot FILE - saves the explored search tree as Graphviz DOT file
+    --seed N - seed of simulated_annealing and genetic, 42 by default
+    --render MODE - draws the board after every move: side (all at once) or animate
+    --grid STYLE - unicode (default) or ascii grid of --render, the moved tile is in brackets
+    --delay SECONDS - pause between the boards of --render animate, 0.3 by default
+
+batch solves every board of FILE, or of standard input without FILE or with -, using
+the flags of solve except --dot and --render; a board per line, empty lines and text after # are skipped.
+
+verify replays MOVES, the letters U, R, D and L moving the blank, and checks that they reach
+the goal; --show prints the board after every move.
+
+generate prints --count random boards (10 by default) that can reach the goal: uniformly
+random ones, boards after a random walk of --walk moves from the goal, or boards exactly
+--distance optimal moves away. The same --seed gives the same boards.
+
+bench runs --algorithms (all by default) with every one of --heuristics (manhattan,misplaced)
+on --boards random boards (3) of every optimal depth in --depths (4,8,12,16,20) and prints
+a table of solved boards, mean expanded nodes, effective branching factor b*, moves and time;
+--seed applies to every board, --max-nodes (100000) and --timeout (1) to every board of the
+algorithms counting nodes.
+
+play lets you solve BOARD, or a random board, in the terminal: arrows or WASD move the blank,
+u undoes a move, h shows the next optimal move and x solves the board with --delay (0.3)
+seconds between the moves.
+";
+
+/// error of a command whose output was closed, e.g. by `head`; not worth reporting
+pub const OUTPUT_CLOSED: &str = "output closed";
+
+/// full help with the list of algorithms
+pub fn usage() -> String {
+    let mut usage = USAGE.to_string();
+
+    usage.push_str("\nAlgorithms available:\n");
+    for (name, _, description) in ALGORITHMS.iter() {
+        usage.push_str(&format!("    {} - {}\n", name, description));
+    }
+
+    usage
+}
+
+/// runs the command parsed by `args::parse_args`;
+/// returns false when the command did not succeed, e.g. there is no solution
+pub fn run(command: Command, out: &mut dyn Write) -> Result<bool, String> {
+    match command {
+        Command::Solve(options) => solve::solve(&options, out),
+        Command::Batch(options) => match options.input {
+            Some(ref path) => {
+                let file =
+                    File::open(path).map_err(|err| format!("failed to open {}: {}", path, err))?;
+                batch::batch(&options, &mut BufReader::new(file), out)
+            }
+            None => batch::batch(&options, &mut io::stdin().lock(), out),
+        },
+        Command::Check(options) => solve::check(&options, out),
+        Command::Verify(options) => verify::verify(&options, out),
+        Command::Generate(options) => generate::generate(&options, out),
+        Command::Bench(options) => bench::bench(&options, out),
+        Command::Play(options) => play::play_in_terminal(&options, out),
+        Command::Help => {
+            write!(out, "{}", usage()).map_err(solve::io_error)?;
+            Ok(true)
+        }
+    }
+}
@@ -0,0 +1,917 @@
+//! Parsing of the command line arguments
+//! Flags are written as `--name value` or `--name=value` and may come in any order
+//! after the subcommand, switches like `--show` take no value;
+//! unknown flags and values are errors instead of being ignored.
+
+use std::time::Duration;
+
+use crate::eight_puzzle::{
+    parse_moves, Heuristic, PuzzleStateRow, TileDirection, DEFAULT_GOAL, PUZZLE_SIZE,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Algorithm {
+    DepthFirst,
+    BreadthFirst,
+    UniformCost,
+    DepthLimited,
+    IterativeDeepening,
+    Bidirectional,
+    HillClimbing,
+    SimulatedAnnealing,
+    AStar,
+    Beam,
+    Genetic,
+}
+
+/// name used on the command line and description of every algorithm
+pub const ALGORITHMS: [(&str, Algorithm, &str); 11] = [
+    (
+        "depth_first",
+        Algorithm::DepthFirst,
+        "takes the last action first and follows it as deep as it can",
+    ),
+    (
+        "breadth_first",
+        Algorithm::BreadthFirst,
+        "tries every action on the same level",
+    ),
+    (
+        "uniform_cost",
+        Algorithm::UniformCost,
+        "takes the cheapest route first, here the shallowest as every move costs 1",
+    ),
+    (
+        "depth_limited",
+        Algorithm::DepthLimited,
+        "depth_first that stops at --depth",
+    ),
+    (
+        "iterative_deepening",
+        Algorithm::IterativeDeepening,
+        "depth_limited with growing depth until the goal is found",
+    ),
+    (
+        "bidirectional",
+        Algorithm::Bidirectional,
+        "breadth_first from the board and from the goal until they meet",
+    ),
+    (
+        "astar",
+        Algorithm::AStar,
+        "takes the route with the lowest cost plus heuristic first",
+    ),
+    (
+        "beam",
+        Algorithm::Beam,
+        "breadth_first that keeps only the best nodes of every level",
+    ),
+    (
+        "genetic",
+        Algorithm::Genetic,
+        "evolves random move sequences until one of them solves the puzzle",
+    ),
+    (
+        "hill_climbing",
+        Algorithm::HillClimbing,
+        "moves to the neighbor closest to the goal, may get stuck at local optimum",
+    ),
+    (
+        "simulated_annealing",
+        Algorithm::SimulatedAnnealing,
+        "takes random moves, accepts worse ones less often as it cools down",
+    ),
+];
+
+impl Algorithm {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        let name = name.trim().to_lowercase();
+
+        ALGORITHMS
+            .iter()
+            .find(|(algorithm_name, _, _)| *algorithm_name == name)
+            .map(|&(_, algorithm, _)| algorithm)
+            .ok_or_else(|| {
+                let names: Vec<&str> = ALGORITHMS.iter().map(|(name, _, _)| *name).collect();
+                format!(
+                    "unknown algorithm {:?}, use one of: {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+
+    pub fn name(&self) -> &'static str {
+        ALGORITHMS
+            .iter()
+            .find(|(_, algorithm, _)| algorithm == self)
+            .map(|(name, _, _)| *name)
+            .unwrap()
+    }
+
+    /// true for the algorithms guided by --heuristic
+    pub fn uses_heuristic(&self) -> bool {
+        matches!(
+            self,
+            Algorithm::AStar
+                | Algorithm::Beam
+                | Algorithm::Genetic
+                | Algorithm::HillClimbing
+                | Algorithm::SimulatedAnnealing
+        )
+    }
+
+    /// true for the algorithms reporting their nodes to the observers;
+    /// only these count nodes and stop at --max-nodes and --timeout
+    pub fn counts_nodes(&self) -> bool {
+        !matches!(
+            self,
+            Algorithm::Genetic | Algorithm::HillClimbing | Algorithm::SimulatedAnnealing
+        )
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+    /// moves as letters, e.g. `URRD`
+    Compact,
+}
+
+impl OutputFormat {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "compact" => Ok(OutputFormat::Compact),
+            _ => Err(format!(
+                "unknown format {:?}, use one of: text, json, csv, compact",
+                name
+            )),
+        }
+    }
+}
+
+/// how `solve --render` shows the boards along the solution
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RenderMode {
+    /// all boards at once, as many in a row as fit the line
+    SideBySide,
+    /// one board after another in the same place
+    Animate,
+}
+
+impl RenderMode {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_lowercase().as_str() {
+            "side" => Ok(RenderMode::SideBySide),
+            "animate" => Ok(RenderMode::Animate),
+            _ => Err(format!(
+                "unknown render mode {:?}, use one of: side, animate",
+                name
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GridStyle {
+    Ascii,
+    /// box drawing characters
+    Unicode,
+}
+
+impl GridStyle {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_lowercase().as_str() {
+            "ascii" => Ok(GridStyle::Ascii),
+            "unicode" => Ok(GridStyle::Unicode),
+            _ => Err(format!(
+                "unknown grid {:?}, use one of: ascii, unicode",
+                name
+            )),
+        }
+    }
+}
+
+pub fn heuristic_from_name(name: &str) -> Result<Heuristic, String> {
+    match name.trim().to_lowercase().as_str() {
+        "manhattan" => Ok(Heuristic::ManhattanDistance),
+        "misplaced" => Ok(Heuristic::MisplacedTiles),
+        _ => Err(format!(
+            "unknown heuristic {:?}, use one of: manhattan, misplaced",
+            name
+        )),
+    }
+}
+
+pub fn heuristic_name(heuristic: Heuristic) -> &'static str {
+    match heuristic {
+        Heuristic::ManhattanDistance => "manhattan",
+        Heuristic::MisplacedTiles => "misplaced",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveOptions {
+    pub board: PuzzleStateRow,
+    pub algorithm: Algorithm,
+    pub heuristic: Heuristic,
+    /// depth limit of depth_limited search
+    pub depth: usize,
+    pub goal: PuzzleStateRow,
+    /// search gives up after expanding this many nodes
+    pub max_nodes: Option<usize>,
+    pub timeout: Option<Duration>,
+    pub format: OutputFormat,
+    /// file for the Graphviz DOT export of the search tree
+    pub dot: Option<String>,
+    /// seed of the randomized algorithms
+    pub seed: u64,
+    /// boards of the solution drawn after the text output
+    pub render: Option<RenderMode>,
+    pub grid: GridStyle,
+    /// pause between the boards of the animation
+    pub delay: Duration,
+}
+
+impl SolveOptions {
+    pub fn new(board: PuzzleStateRow) -> Self {
+        SolveOptions {
+            board,
+            algorithm: Algorithm::BreadthFirst,
+            heuristic: Heuristic::ManhattanDistance,
+            depth: DEFAULT_DEPTH,
+            goal: DEFAULT_GOAL,
+            max_nodes: None,
+            timeout: None,
+            format: OutputFormat::Text,
+            dot: None,
+            seed: DEFAULT_SEED,
+            render: None,
+            grid: GridStyle::Unicode,
+            delay: DEFAULT_DELAY,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckOptions {
+    pub board: PuzzleStateRow,
+    pub goal: PuzzleStateRow,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifyOptions {
+    pub board: PuzzleStateRow,
+    pub moves: Vec<TileDirection>,
+    pub goal: PuzzleStateRow,
+    /// print the board after every move
+    pub show: bool,
+}
+
+/// how `generate` picks the boards
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BoardSource {
+    /// uniform random permutation that can reach the goal
+    Permutation,
+    /// random walk of the blank with the given number of moves from the goal
+    Walk(usize),
+    /// board with exactly this optimal number of moves
+    Distance(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerateOptions {
+    pub count: usize,
+    pub source: BoardSource,
+    pub goal: PuzzleStateRow,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchOptions {
+    pub algorithms: Vec<Algorithm>,
+    /// heuristics tried with every algorithm that uses one
+    pub heuristics: Vec<Heuristic>,
+    /// optimal number of moves of the boards in every group
+    pub depths: Vec<usize>,
+    /// boards in every group
+    pub boards: usize,
+    pub max_nodes: Option<usize>,
+    pub timeout: Option<Duration>,
+    pub seed: u64,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            algorithms: ALGORITHMS
+                .iter()
+                .map(|&(_, algorithm, _)| algorithm)
+                .collect(),
+            heuristics: vec![Heuristic::ManhattanDistance, Heuristic::MisplacedTiles],
+            depths: vec![4, 8, 12, 16, 20],
+            boards: 3,
+            max_nodes: Some(100_000),
+            timeout: Some(Duration::from_secs(1)),
+            seed: DEFAULT_SEED,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayOptions {
+    /// None starts from a random board
+    pub board: Option<PuzzleStateRow>,
+    pub goal: PuzzleStateRow,
+    /// seed of the random board, None picks a new board every time
+    pub seed: Option<u64>,
+    /// pause between the moves of the auto-solve animation
+    pub delay: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchOptions {
+    /// file with one board per line, None reads standard input
+    pub input: Option<String>,
+    /// options used for every board; the board itself comes from the input
+    pub solve: SolveOptions,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Solve(SolveOptions),
+    Batch(BatchOptions),
+    Check(CheckOptions),
+    Verify(VerifyOptions),
+    Generate(GenerateOptions),
+    Bench(BenchOptions),
+    Play(PlayOptions),
+    Help,
+}
+
+pub const DEFAULT_DEPTH: usize = 10;
+pub const DEFAULT_SEED: u64 = 42;
+pub const DEFAULT_COUNT: usize = 10;
+pub const DEFAULT_DELAY: Duration = Duration::from_millis(300);
+
+/// parses the arguments without the program name
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return Ok(Command::Help),
+    };
+
+    // help is a switch of every command, `solve --help` prints the usage as well
+    if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        return Ok(Command::Help);
+    }
+
+    match command {
+        "help" => Ok(Command::Help),
+        "solve" => parse_solve(rest),
+        "batch" => parse_batch(rest),
+        "check" => parse_check(rest),
+        "verify" => parse_verify(rest),
+        "generate" => parse_generate(rest),
+        "bench" => parse_bench(rest),
+        "play" => parse_play(rest),
+        // the old form without subcommand: BOARD [ALGORITHM] [--dot FILE]
+        _ if command.contains(',') => parse_solve(args),
+        _ => Err(format!("unknown command {:?}", command)),
+    }
+}
+
+fn parse_solve(args: &[String]) -> Result<Command, String> {
+    let (positional, flags) = split_flags(args)?;
+    let (board, algorithm) = match positional.as_slice() {
+        [board] => (board, None),
+        [board, algorithm] => (board, Some(algorithm)),
+        [] => return Err("solve needs a board".to_string()),
+        _ => return Err(format!("unexpected argument {:?}", positional[2])),
+    };
+
+    let mut options = SolveOptions::new(parse_board(board)?);
+    if let Some(algorithm) = algorithm {
+        options.algorithm = Algorithm::from_name(algorithm)?;
+    }
+
+    for (name, value) in flags.iter() {
+        match name.as_str() {
+            "dot" => options.dot = Some(value.clone()),
+            "render" => options.render = Some(RenderMode::from_name(value)?),
+            "grid" => options.grid = GridStyle::from_name(value)?,
+            "delay" => options.delay = parse_seconds(name, value)?,
+            _ => apply_solve_flag(&mut options, name, value, "solve")?,
+        }
+    }
+    if options.render.is_some() && options.format != OutputFormat::Text {
+        return Err("--render works only with the text format".to_string());
+    }
+    check_limits(&options)?;
+
+    Ok(Command::Solve(options))
+}
+
+fn parse_batch(args: &[String]) -> Result<Command, String> {
+    let (positional, flags) = split_flags(args)?;
+    let input = match positional.as_slice() {
+        [] => None,
+        [input] if input == "-" => None,
+        [input] => Some(input.clone()),
+        _ => return Err(format!("unexpected argument {:?}", positional[1])),
+    };
+
+    let mut solve = SolveOptions::new(DEFAULT_GOAL);
+    for (name, value) in flags.iter() {
+        apply_solve_flag(&mut solve, name, value, "batch")?;
+    }
+    check_limits(&solve)?;
+
+    Ok(Command::Batch(BatchOptions { input, solve }))
+}
+
+// local searches and the genetic algorithm stop on their own, the limits would be ignored
+fn check_limits(options: &SolveOptions) -> Result<(), String> {
+    if !options.algorithm.counts_nodes()
+        && (options.max_nodes.is_some() || options.timeout.is_some())
+    {
+        return Err(format!(
+            "{} does not support --max-nodes and --timeout",
+            options.algorithm.name()
+        ));
+    }
+
+    Ok(())
+}
+
+// flags shared by solve and batch
+fn apply_solve_flag(
+    options: &mut SolveOptions,
+    name: &str,
+    value: &str,
+    command: &str,
+) -> Result<(), String> {
+    match name {
+        "algorithm" => options.algorithm = Algorithm::from_name(value)?,
+        "heuristic" => options.heuristic = heuristic_from_name(value)?,
+        "depth" => options.depth = parse_number(name, value)?,
+        "goal" => options.goal = parse_board(value)?,
+        "max-nodes" => options.max_nodes = Some(parse_number(name, value)?),
+        "timeout" => options.timeout = Some(parse_seconds(name, value)?),
+        "format" => options.format = OutputFormat::from_name(value)?,
+        "seed" => options.seed = parse_number(name, value)?,
+        _ => return Err(format!("unknown flag --{} for {}", name, command)),
+    }
+
+    Ok(())
+}
+
+fn parse_check(args: &[String]) -> Result<Command, String> {
+    let (positional, flags) = split_flags(args)?;
+    let board = match positional.as_slice() {
+        [board] => parse_board(board)?,
+        [] => return Err("check needs a board".to_string()),
+        _ => return Err(format!("unexpected argument {:?}", positional[1])),
+    };
+
+    let mut options = CheckOptions {
+        board,
+        goal: DEFAULT_GOAL,
+    };
+    for (name, value) in flags.iter() {
+        match name.as_str() {
+            "goal" => options.goal = parse_board(value)?,
+            _ => return Err(format!("unknown flag --{} for check", name)),
+        }
+    }
+
+    Ok(Command::Check(options))
+}
+
+fn parse_verify(args: &[String]) -> Result<Command, String> {
+    let (positional, flags) = split_flags(args)?;
+    let (board, moves) = match positional.as_slice() {
+        [board] => (board, ""),
+        [board, moves] => (board, moves.as_str()),
+        [] => return Err("verify needs a board and moves".to_string()),
+        _ => return Err(format!("unexpected argument {:?}", positional[2])),
+    };
+
+    let mut options = VerifyOptions {
+        board: parse_board(board)?,
+        moves: parse_moves(moves)?,
+        goal: DEFAULT_GOAL,
+        show: false,
+    };
+    for (name, value) in flags.iter() {
+        match name.as_str() {
+            "goal" => options.goal = parse_board(value)?,
+            "show" => options.show = true,
+            _ => return Err(format!("unknown flag --{} for verify", name)),
+        }
+    }
+
+    Ok(Command::Verify(options))
+}
+
+fn parse_generate(args: &[String]) -> Result<Command, String> {
+    let (positional, flags) = split_flags(args)?;
+    if let Some(arg) = positional.first() {
+        return Err(format!("unexpected argument {:?}", arg));
+    }
+
+    let mut options = GenerateOptions {
+        count: DEFAULT_COUNT,
+        source: BoardSource::Permutation,
+        goal: DEFAULT_GOAL,
+        seed: DEFAULT_SEED,
+    };
+    for (name, value) in flags.iter() {
+        match name.as_str() {
+            "count" => options.count = parse_number(name, value)?,
+            "goal" => options.goal = parse_board(value)?,
+            "seed" => options.seed = parse_number(name, value)?,
+            "walk" | "distance" => {
+                if options.source != BoardSource::Permutation {
+                    return Err("use only one of --walk and --distance".to_string());
+                }
+                let moves = parse_number(name, value)?;
+                options.source = match name.as_str() {
+                    "walk" => BoardSource::Walk(moves),
+                    _ => BoardSource::Distance(moves),
+                };
+            }
+            _ => return Err(format!("unknown flag --{} for generate", name)),
+        }
+    }
+
+    Ok(Command::Generate(options))
+}
+
+fn parse_bench(args: &[String]) -> Result<Command, String> {
+    let (positional, flags) = split_flags(args)?;
+    if let Some(arg) = positional.first() {
+        return Err(format!("unexpected argument {:?}", arg));
+    }
+
+    let mut options = BenchOptions::default();
+    for (name, value) in flags.iter() {
+        match name.as_str() {
+            "algorithms" => options.algorithms = parse_list(value, Algorithm::from_name)?,
+            "heuristics" => options.heuristics = parse_list(value, heuristic_from_name)?,
+            "depths" => options.depths = parse_list(value, |item| parse_number(name, item))?,
+            "boards" => options.boards = parse_number(name, value)?,
+            "max-nodes" => options.max_nodes = Some(parse_number(name, value)?),
+            "timeout" => options.timeout = Some(parse_seconds(name, value)?),
+            "seed" => options.seed = parse_number(name, value)?,
+            _ => return Err(format!("unknown flag --{} for bench", name)),
+        }
+    }
+
+    Ok(Command::Bench(options))
+}
+
+fn parse_play(args: &[String]) -> Result<Command, String> {
+    let (positional, flags) = split_flags(args)?;
+    let board = match positional.as_slice() {
+        [] => None,
+        [board] => Some(parse_board(board)?),
+        _ => return Err(format!("unexpected argument {:?}", positional[1])),
+    };
+
+    let mut options = PlayOptions {
+        board,
+        goal: DEFAULT_GOAL,
+        seed: None,
+        delay: DEFAULT_DELAY,
+    };
+    for (name, value) in flags.iter() {
+        match name.as_str() {
+            "goal" => options.goal = parse_board(value)?,
+            "seed" => options.seed = Some(parse_number(name, value)?),
+            "delay" => options.delay = parse_seconds(name, value)?,
+            _ => return Err(format!("unknown flag --{} for play", name)),
+        }
+    }
+
+    Ok(Command::Play(options))
+}
+
+// comma separated values, e.g. `astar,beam`
+fn parse_list<T>(value: &str, parse: impl Fn(&str) -> Result<T, String>) -> Result<Vec<T>, String> {
+    value.split(',').map(|item| parse(item.trim())).collect()
+}
+
+type Flags = Vec<(String, String)>;
+
+// flags without a value
+const SWITCHES: [&str; 1] = ["show"];
+
+// separates positional arguments from flags and their values
+fn split_flags(args: &[String]) -> Result<(Vec<String>, Flags), String> {
+    let mut positional = vec![];
+    let mut flags = vec![];
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.strip_prefix("--") {
+            Some(flag) => {
+                let (name, value) = match flag.split_once('=') {
+                    Some((name, value)) => (name.to_string(), value.to_string()),
+                    None if SWITCHES.contains(&flag) => (flag.to_string(), String::new()),
+                    None => {
+                        let value = iter
+                            .next()
+                            .ok_or_else(|| format!("--{} requires a value", flag))?;
+                        (flag.to_string(), value.clone())
+                    }
+                };
+                flags.push((name, value));
+            }
+            None => positional.push(arg.clone()),
+        }
+    }
+
+    Ok((positional, flags))
+}
+
+/// reads the board as 9 comma separated numbers, every number from 0 to 8 exactly once
+pub fn parse_board(text: &str) -> Result<PuzzleStateRow, String> {
+    let numbers = text
+        .split(',')
+        .map(|item| {
+            item.trim()
+                .parse::<u8>()
+                .map_err(|_| format!("{:?} is not a number", item.trim()))
+        })
+        .collect::<Result<Vec<u8>, String>>()?;
+
+    if numbers.len() != PUZZLE_SIZE {
+        return Err(format!(
+            "board must have {} numbers, got {}",
+            PUZZLE_SIZE,
+            numbers.len()
+        ));
+    }
+
+    let mut board: PuzzleStateRow = [0; PUZZLE_SIZE];
+    board.copy_from_slice(&numbers);
+    for tile in 0..PUZZLE_SIZE as u8 {
+        if !board.contains(&tile) {
+            return Err(format!(
+                "board must contain every number from 0 to 8, {} is missing",
+                tile
+            ));
+        }
+    }
+
+    Ok(board)
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("--{} expects a number, got {:?}", name, value))
+}
+
+fn parse_seconds(name: &str, value: &str) -> Result<Duration, String> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        // negative, infinite and too large values do not fit into a duration
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("--{} expects seconds, got {:?}", name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_solve_with_flags() {
+        let command =
+            parse_args(&args("solve 1,2,3,4,5,0,7,8,6 --algorithm astar --heuristic=misplaced --max-nodes 100 --timeout 1.5"))
+                .unwrap();
+
+        let mut expected = SolveOptions::new([1, 2, 3, 4, 5, 0, 7, 8, 6]);
+        expected.algorithm = Algorithm::AStar;
+        expected.heuristic = Heuristic::MisplacedTiles;
+        expected.max_nodes = Some(100);
+        expected.timeout = Some(Duration::from_millis(1500));
+        assert_eq!(Command::Solve(expected), command);
+    }
+
+    #[test]
+    fn test_old_positional_form_still_works() {
+        let command = parse_args(&args("1,2,3,4,5,0,7,8,6 depth_first --dot tree.dot")).unwrap();
+
+        match command {
+            Command::Solve(options) => {
+                assert_eq!(Algorithm::DepthFirst, options.algorithm);
+                assert_eq!(Some("tree.dot".to_string()), options.dot);
+            }
+            _ => panic!("expected solve, got {:?}", command),
+        }
+    }
+
+    #[test]
+    fn test_unknown_values_are_errors() {
+        let board = "1,2,3,4,5,0,7,8,6";
+
+        assert!(parse_args(&args(&format!("solve {} --algorithm dfs", board))).is_err());
+        assert!(parse_args(&args(&format!("solve {} --heuristic euclid", board))).is_err());
+        assert!(parse_args(&args(&format!("solve {} --depth ten", board))).is_err());
+        assert!(parse_args(&args(&format!("solve {} --format xml", board))).is_err());
+        assert!(parse_args(&args(&format!("solve {} --verbose yes", board))).is_err());
+        assert!(parse_args(&args(&format!("solve {} --depth", board))).is_err());
+        assert!(parse_args(&args(&format!("solve {} --timeout -1", board))).is_err());
+        assert!(parse_args(&args(&format!("solve {} --timeout 1e30", board))).is_err());
+        assert!(parse_args(&args("play --delay 1e300")).is_err());
+        assert!(parse_args(&args(&format!("check {} --depth 3", board))).is_err());
+        assert!(parse_args(&args("solver 1,2,3")).is_err());
+    }
+
+    #[test]
+    fn test_limits_only_for_algorithms_counting_nodes() {
+        let board = "1,2,3,4,5,0,7,8,6";
+
+        for algorithm in ["hill_climbing", "simulated_annealing", "genetic"] {
+            let line = format!("solve {} --algorithm {} --timeout 1", board, algorithm);
+            assert_eq!(
+                Some(format!(
+                    "{} does not support --max-nodes and --timeout",
+                    algorithm
+                )),
+                parse_args(&args(&line)).err()
+            );
+            let line = format!("batch --max-nodes 10 --algorithm {}", algorithm);
+            assert!(parse_args(&args(&line)).is_err());
+            let line = format!("solve {} --algorithm {}", board, algorithm);
+            assert!(parse_args(&args(&line)).is_ok());
+        }
+        let line = format!("solve {} --algorithm beam --max-nodes 10", board);
+        assert!(parse_args(&args(&line)).is_ok());
+    }
+
+    #[test]
+    fn test_help_is_a_switch_of_every_command() {
+        for line in [
+            "--help",
+            "help",
+            "solve --help",
+            "solve 1,2,3,4,5,0,7,8,6 -h",
+            "batch boards.txt --format csv --help",
+            "verify -h",
+            "bench --depths 4 --help",
+            "1,2,3,4,5,0,7,8,6 --help",
+        ] {
+            assert!(
+                matches!(parse_args(&args(line)), Ok(Command::Help)),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch_reads_file_or_stdin() {
+        let from_file = parse_args(&args("batch boards.txt --algorithm astar")).unwrap();
+        let from_stdin = parse_args(&args("batch - --max-nodes 10 --format csv")).unwrap();
+
+        match (from_file, from_stdin) {
+            (Command::Batch(file), Command::Batch(stdin)) => {
+                assert_eq!(Some("boards.txt".to_string()), file.input);
+                assert_eq!(Algorithm::AStar, file.solve.algorithm);
+                assert_eq!(None, stdin.input);
+                assert_eq!(Some(10), stdin.solve.max_nodes);
+                assert_eq!(OutputFormat::Csv, stdin.solve.format);
+            }
+            other => panic!("expected batch commands, got {:?}", other),
+        }
+        assert!(parse_args(&args("batch boards.txt --dot tree.dot")).is_err());
+    }
+
+    #[test]
+    fn test_verify_takes_moves_and_switches() {
+        let command = parse_args(&args("verify 1,2,3,4,0,5,7,8,6 rd --show")).unwrap();
+
+        assert_eq!(
+            Command::Verify(VerifyOptions {
+                board: [1, 2, 3, 4, 0, 5, 7, 8, 6],
+                moves: vec![TileDirection::Right, TileDirection::Down],
+                goal: DEFAULT_GOAL,
+                show: true,
+            }),
+            command
+        );
+        assert!(parse_args(&args("verify 1,2,3,4,0,5,7,8,6 RX")).is_err());
+        assert!(parse_args(&args("verify 1,2,3,4,0,5,7,8,6 RD --goal")).is_err());
+    }
+
+    #[test]
+    fn test_generate_picks_one_source() {
+        let command = parse_args(&args("generate --count 3 --distance 12 --seed 7")).unwrap();
+
+        assert_eq!(
+            Command::Generate(GenerateOptions {
+                count: 3,
+                source: BoardSource::Distance(12),
+                goal: DEFAULT_GOAL,
+                seed: 7,
+            }),
+            command
+        );
+        assert!(parse_args(&args("generate --walk 10 --distance 12")).is_err());
+        assert!(parse_args(&args("generate 5")).is_err());
+    }
+
+    #[test]
+    fn test_bench_takes_lists() {
+        let command = parse_args(&args(
+            "bench --algorithms astar,beam --heuristics misplaced --depths 2,6",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            Command::Bench(BenchOptions {
+                algorithms: vec![Algorithm::AStar, Algorithm::Beam],
+                heuristics: vec![Heuristic::MisplacedTiles],
+                depths: vec![2, 6],
+                ..BenchOptions::default()
+            }),
+            command
+        );
+        assert!(parse_args(&args("bench --depths 2,x")).is_err());
+        assert!(parse_args(&args("bench --algorithms astar,dfs")).is_err());
+    }
+
+    #[test]
+    fn test_play_board_is_optional() {
+        let command = parse_args(&args("play --seed 3 --delay 0.1")).unwrap();
+
+        assert_eq!(
+            Command::Play(PlayOptions {
+                board: None,
+                goal: DEFAULT_GOAL,
+                seed: Some(3),
+                delay: Duration::from_millis(100),
+            }),
+            command
+        );
+        match parse_args(&args("play 1,2,3,4,5,0,7,8,6")).unwrap() {
+            Command::Play(options) => assert_eq!(Some([1, 2, 3, 4, 5, 0, 7, 8, 6]), options.board),
+            other => panic!("expected play, got {:?}", other),
+        }
+        assert_eq!(
+            Err("--delay expects seconds, got \"-1\"".to_string()),
+            parse_args(&args("play --delay=-1"))
+        );
+    }
+
+    #[test]
+    fn test_render_flags_of_solve() {
+        let command = parse_args(&args(
+            "solve 1,2,3,4,0,5,7,8,6 --render animate --grid ascii --delay 0.5",
+        ))
+        .unwrap();
+
+        let mut expected = SolveOptions::new([1, 2, 3, 4, 0, 5, 7, 8, 6]);
+        expected.render = Some(RenderMode::Animate);
+        expected.grid = GridStyle::Ascii;
+        expected.delay = Duration::from_millis(500);
+        assert_eq!(Command::Solve(expected), command);
+        assert!(parse_args(&args("solve 1,2,3,4,0,5,7,8,6 --render side --format json")).is_err());
+        assert!(parse_args(&args("solve 1,2,3,4,0,5,7,8,6 --render slideshow")).is_err());
+        assert!(parse_args(&args("batch --render side")).is_err());
+    }
+
+    #[test]
+    fn test_board_must_be_a_permutation() {
+        assert_eq!(
+            Ok([1, 2, 3, 4, 5, 6, 7, 8, 0]),
+            parse_board("1, 2,3,4,5,6,7,8,0")
+        );
+        assert!(parse_board("1,2,3,4,5,6,7,8").is_err());
+        assert!(parse_board("1,1,3,4,5,6,7,8,0").is_err());
+        assert!(parse_board("1,2,3,4,5,6,7,8,x").is_err());
+    }
+
+    #[test]
+    fn test_algorithm_names_round_trip() {
+        for &(name, algorithm, _) in ALGORITHMS.iter() {
+            assert_eq!(Ok(algorithm), Algorithm::from_name(name));
+            assert_eq!(name, algorithm.name());
+        }
+    }
+}
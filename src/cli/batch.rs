@@ -0,0 +1,219 @@
+//! `batch` subcommand
+//! Reads one board per line; empty lines and everything after `#` are skipped.
+//! Every board gets one record, a board that can not be read or solved does not stop the batch.
+
+use std::io::{BufRead, Write};
+use std::time::Instant;
+
+use crate::cli::args::{parse_board, BatchOptions, OutputFormat, SolveOptions};
+use crate::cli::output::{csv_header, write_record, Outcome, Record};
+use crate::cli::solve::{format_board, io_error, run_search};
+use crate::eight_puzzle::EightPuzzleState;
+use crate::search::observers::CountingObserver;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BatchSummary {
+    pub boards: usize,
+    pub solved: usize,
+    pub not_found: usize,
+    pub unsolvable: usize,
+    pub invalid: usize,
+}
+
+impl BatchSummary {
+    fn add(&mut self, record: &Record) {
+        self.boards += 1;
+        match record.outcome {
+            Outcome::Solved(_) => self.solved += 1,
+            Outcome::NotFound(_) => self.not_found += 1,
+            Outcome::Unsolvable => self.unsolvable += 1,
+            Outcome::Invalid(_) => self.invalid += 1,
+        }
+    }
+}
+
+/// solves a single line of the input, None for lines without a board
+pub fn solve_line(line: usize, text: &str, template: &SolveOptions) -> Option<Record> {
+    let board = text.split('#').next().unwrap_or("").trim();
+    if board.is_empty() {
+        return None;
+    }
+
+    let started = Instant::now();
+    let mut counter = CountingObserver::new();
+    let mut cost = 0;
+    // boards are written as 1,2,3,... whatever the spacing of the line, only a board
+    // that can not be read is echoed as it is
+    let (board, outcome) = match parse_board(board) {
+        Err(err) => (board.to_string(), Outcome::Invalid(err)),
+        Ok(row) if !EightPuzzleState::new(row).can_reach(&EightPuzzleState::new(template.goal)) => {
+            (format_board(&row), Outcome::Unsolvable)
+        }
+        Ok(row) => {
+            let options = SolveOptions {
+                board: row,
+                dot: None,
+                ..template.clone()
+            };
+            let outcome = match run_search(&options, &mut None) {
+                Err(err) => Outcome::Invalid(err),
+                Ok(report) => {
+                    counter = report.counter.clone();
+                    cost = report.cost();
+                    report.outcome()
+                }
+            };
+            (format_board(&row), outcome)
+        }
+    };
+
+    Some(Record {
+        line: Some(line),
+        board,
+        outcome,
+        cost,
+        counter,
+        elapsed: started.elapsed(),
+    })
+}
+
+/// returns true if every board was solved; only the text format ends with a summary
+pub fn batch(
+    options: &BatchOptions,
+    input: &mut dyn BufRead,
+    out: &mut dyn Write,
+) -> Result<bool, String> {
+    let format = options.solve.format;
+    let mut summary = BatchSummary::default();
+
+    if format == OutputFormat::Csv {
+        writeln!(out, "{}", csv_header(true)).map_err(io_error)?;
+    }
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.map_err(|err| format!("failed to read input: {}", err))?;
+        if let Some(record) = solve_line(i + 1, &line, &options.solve) {
+            summary.add(&record);
+            write_record(format, &record, out).map_err(io_error)?;
+        }
+    }
+
+    if format == OutputFormat::Text {
+        writeln!(
+            out,
+            "Solved {} of {} boards, {} not found, {} unsolvable, {} invalid",
+            summary.solved, summary.boards, summary.not_found, summary.unsolvable, summary.invalid
+        )
+        .map_err(io_error)?;
+    }
+
+    Ok(summary.solved == summary.boards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::Algorithm;
+    use crate::eight_puzzle::DEFAULT_GOAL;
+
+    const BOARDS: &str = "
+# boards for the nightly run
+1,2,3,4,5,0,7,8,6
+1,2,3,4,5,6,7,8,0   # already solved
+
+2,1,3,4,5,6,7,8,0
+1,2,3
+8,6,7,2,5,4,3,0,1
+";
+
+    fn run(options: &BatchOptions) -> (bool, Vec<String>) {
+        let mut out = vec![];
+        let all_solved = batch(options, &mut BOARDS.as_bytes(), &mut out).unwrap();
+
+        (
+            all_solved,
+            String::from_utf8(out)
+                .unwrap()
+                .lines()
+                .map(String::from)
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_every_board_gets_a_record() {
+        let mut solve = SolveOptions::new(DEFAULT_GOAL);
+        solve.algorithm = Algorithm::AStar;
+        solve.max_nodes = Some(100);
+        let (all_solved, lines) = run(&BatchOptions { input: None, solve });
+
+        assert!(!all_solved);
+        assert_eq!(6, lines.len());
+        assert!(lines[0].starts_with("line 3: 1,2,3,4,5,0,7,8,6 solved in 1 moves: Down ("));
+        assert!(lines[1].starts_with("line 4: 1,2,3,4,5,6,7,8,0 solved in 0 moves ("));
+        assert_eq!("line 6: 2,1,3,4,5,6,7,8,0 unsolvable", lines[2]);
+        assert_eq!(
+            "line 7: 1,2,3 invalid: board must have 9 numbers, got 3",
+            lines[3]
+        );
+        assert!(lines[4].starts_with(
+            "line 8: 8,6,7,2,5,4,3,0,1 no solution, stopped at the limit (expanded 100,"
+        ));
+        assert_eq!(
+            "Solved 2 of 5 boards, 1 not found, 1 unsolvable, 1 invalid",
+            lines[5]
+        );
+    }
+
+    #[test]
+    fn test_lines_without_board_are_skipped() {
+        let template = SolveOptions::new(DEFAULT_GOAL);
+
+        assert_eq!(None, solve_line(1, "   # comment only", &template));
+        assert_eq!(None, solve_line(2, "", &template));
+        assert!(solve_line(3, "1,2,3,4,5,6,7,8,0", &template).is_some());
+    }
+
+    #[test]
+    fn test_boards_are_written_without_spaces() {
+        let mut template = SolveOptions::new(DEFAULT_GOAL);
+        template.format = OutputFormat::Compact;
+        let record = solve_line(1, " 1, 2, 3,4,5,6,7, 8,0 ", &template).unwrap();
+        assert_eq!("1,2,3,4,5,6,7,8,0", record.board);
+
+        let record = solve_line(2, "2, 1,3,4,5,6,7,8,0", &template).unwrap();
+        assert_eq!("2,1,3,4,5,6,7,8,0", record.board);
+
+        // the line is kept as it is when it is not a board
+        let record = solve_line(3, "1, 2, x", &template).unwrap();
+        assert_eq!("1, 2, x", record.board);
+    }
+
+    #[test]
+    fn test_machine_readable_formats_have_no_summary() {
+        let mut solve = SolveOptions::new(DEFAULT_GOAL);
+        solve.max_nodes = Some(100);
+        solve.format = OutputFormat::Compact;
+        let (_, compact) = run(&BatchOptions { input: None, solve });
+
+        assert_eq!(
+            vec![
+                "1,2,3,4,5,0,7,8,6 D",
+                "1,2,3,4,5,6,7,8,0",
+                "2,1,3,4,5,6,7,8,0 unsolvable",
+                "1,2,3 invalid",
+                "8,6,7,2,5,4,3,0,1 limit_reached",
+            ],
+            compact
+        );
+
+        let mut solve = SolveOptions::new(DEFAULT_GOAL);
+        solve.max_nodes = Some(100);
+        solve.format = OutputFormat::Csv;
+        let (_, csv) = run(&BatchOptions { input: None, solve });
+
+        assert_eq!(6, csv.len());
+        assert_eq!(csv_header(true), csv[0]);
+        assert!(csv[1].starts_with("3,\"1,2,3,4,5,0,7,8,6\",solved,D,1,1,"));
+    }
+}
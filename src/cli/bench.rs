@@ -0,0 +1,325 @@
+//! `bench` subcommand
+//! Runs the algorithms over random boards grouped by their optimal number of moves and prints
+//! a Markdown table, a row per group as soon as it is measured. Expanded nodes and time are
+//! means over all runs, so a failed run counts with the work done until the limit;
+//! moves and the effective branching factor are means over the solved runs. A run is solved
+//! only if its moves replay from the board to the goal and are not fewer than the optimum.
+//! Local searches and the genetic algorithm do not report nodes, so they get only time and moves,
+//! and they stop on their own rather than at the node and time limits.
+//! source:
+//! AIMA 3rd edition, chapter 3.6.1 The effect of heuristic accuracy on performance
+
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use crate::cli::args::{heuristic_name, Algorithm, BenchOptions, SolveOptions};
+use crate::cli::solve::{io_error, run_search};
+use crate::cli::verify::verify_moves;
+use crate::eight_puzzle::{Heuristic, PuzzleStateRow, TileDirection, DEFAULT_GOAL};
+use crate::generator::DistanceOracle;
+use crate::random::Random;
+
+/// measurements of an algorithm on a group of boards
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchRow {
+    pub depth: usize,
+    pub algorithm: Algorithm,
+    /// None for the algorithms without heuristic
+    pub heuristic: Option<Heuristic>,
+    pub runs: usize,
+    pub solved: usize,
+    pub expanded: usize,
+    /// sum of solution lengths of the solved runs
+    pub moves: usize,
+    /// effective branching factors of the solved runs
+    pub branching: Vec<f64>,
+    pub elapsed: Duration,
+}
+
+impl BenchRow {
+    pub fn mean_expanded(&self) -> f64 {
+        self.expanded as f64 / self.runs.max(1) as f64
+    }
+
+    pub fn mean_moves(&self) -> Option<f64> {
+        match self.solved {
+            0 => None,
+            solved => Some(self.moves as f64 / solved as f64),
+        }
+    }
+
+    pub fn mean_branching(&self) -> Option<f64> {
+        match self.branching.len() {
+            0 => None,
+            len => Some(self.branching.iter().sum::<f64>() / len as f64),
+        }
+    }
+
+    pub fn mean_ms(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1000.0 / self.runs.max(1) as f64
+    }
+}
+
+/// b* of a uniform tree of the given depth with as many nodes as the search generated,
+/// i.e. the solution of N + 1 = 1 + b* + (b*)^2 + ... + (b*)^d
+pub fn effective_branching_factor(generated: usize, depth: usize) -> Option<f64> {
+    if generated == 0 || depth == 0 {
+        return None;
+    }
+
+    let nodes = |b: f64| (0..=depth as i32).map(|i| b.powi(i)).sum::<f64>();
+    let target = generated as f64 + 1.0;
+    let (mut low, mut high) = (0.0, generated as f64);
+    for _ in 0..100 {
+        let middle = (low + high) / 2.0;
+        if nodes(middle) < target {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    Some((low + high) / 2.0)
+}
+
+// the moves must take the board to the goal and can not beat the optimal depth, so a broken
+// algorithm is not counted as solving the board
+fn is_valid_solution(board: PuzzleStateRow, moves: &[TileDirection], depth: usize) -> bool {
+    verify_moves(board, moves, DEFAULT_GOAL).reached_goal && moves.len() >= depth
+}
+
+// boards of every depth, distinct as long as there are enough of them
+fn board_suite(options: &BenchOptions) -> Result<Vec<(usize, Vec<PuzzleStateRow>)>, String> {
+    let oracle = DistanceOracle::new(DEFAULT_GOAL);
+    let mut rng = Random::new(options.seed);
+
+    options
+        .depths
+        .iter()
+        .map(|&depth| {
+            let mut boards = oracle.boards_at(depth).to_vec();
+            if boards.is_empty() {
+                return Err(format!(
+                    "no board is {} moves from the goal, the farthest are {}",
+                    depth,
+                    oracle.max_distance()
+                ));
+            }
+            rng.shuffle(&mut boards);
+            let boards = (0..options.boards)
+                .map(|i| boards[i % boards.len()])
+                .collect();
+            Ok((depth, boards))
+        })
+        .collect()
+}
+
+/// runs every algorithm, and heuristic if it uses one, on every group of boards
+pub fn run_bench(
+    options: &BenchOptions,
+    on_row: &mut dyn FnMut(&BenchRow) -> Result<(), String>,
+) -> Result<Vec<BenchRow>, String> {
+    let mut configs: Vec<(Algorithm, Option<Heuristic>)> = vec![];
+    for &algorithm in options.algorithms.iter() {
+        if algorithm.uses_heuristic() {
+            configs.extend(options.heuristics.iter().map(|&h| (algorithm, Some(h))));
+        } else {
+            configs.push((algorithm, None));
+        }
+    }
+
+    let mut rows = vec![];
+    for (depth, boards) in board_suite(options)? {
+        for &(algorithm, heuristic) in configs.iter() {
+            let mut row = BenchRow {
+                depth,
+                algorithm,
+                heuristic,
+                runs: 0,
+                solved: 0,
+                expanded: 0,
+                moves: 0,
+                branching: vec![],
+                elapsed: Duration::default(),
+            };
+
+            for &board in boards.iter() {
+                let mut solve = SolveOptions::new(board);
+                solve.algorithm = algorithm;
+                solve.heuristic = heuristic.unwrap_or(solve.heuristic);
+                // depth_limited gets the optimal depth, deeper limits only add work
+                solve.depth = depth;
+                if algorithm.counts_nodes() {
+                    solve.max_nodes = options.max_nodes;
+                    solve.timeout = options.timeout;
+                }
+                solve.seed = options.seed;
+
+                let started = Instant::now();
+                let report = run_search(&solve, &mut None)?;
+                row.elapsed += started.elapsed();
+                row.runs += 1;
+                row.expanded += report.counter.expanded;
+
+                let moves = report.moves();
+                if report.solution.is_some() && is_valid_solution(board, &moves, depth) {
+                    let moves = moves.len();
+                    row.solved += 1;
+                    row.moves += moves;
+                    if algorithm.counts_nodes() {
+                        row.branching
+                            .extend(effective_branching_factor(report.counter.generated, moves));
+                    }
+                }
+            }
+
+            on_row(&row)?;
+            rows.push(row);
+        }
+    }
+
+    Ok(rows)
+}
+
+pub fn bench(options: &BenchOptions, out: &mut dyn Write) -> Result<bool, String> {
+    let limits = match (options.max_nodes, options.timeout) {
+        (Some(nodes), Some(timeout)) => {
+            format!("{} nodes or {} s", nodes, timeout.as_secs_f64())
+        }
+        (Some(nodes), None) => format!("{} nodes", nodes),
+        (None, Some(timeout)) => format!("{} s", timeout.as_secs_f64()),
+        (None, None) => "none".to_string(),
+    };
+    writeln!(
+        out,
+        "{} boards per depth, seed {}, limit per board of the algorithms counting nodes: {}\n",
+        options.boards, options.seed, limits
+    )
+    .map_err(io_error)?;
+    writeln!(
+        out,
+        "| depth | algorithm           | heuristic | solved |   expanded |     b* |  moves |        ms |"
+    )
+    .map_err(io_error)?;
+    writeln!(
+        out,
+        "|------:|---------------------|-----------|-------:|-----------:|-------:|-------:|----------:|"
+    )
+    .map_err(io_error)?;
+
+    run_bench(options, &mut |row| {
+        write_row(row, out).map_err(io_error)?;
+        out.flush().map_err(io_error)
+    })?;
+
+    Ok(true)
+}
+
+fn write_row(row: &BenchRow, out: &mut dyn Write) -> std::io::Result<()> {
+    let optional = |value: Option<f64>, precision: usize| match value {
+        Some(value) => format!("{:.*}", precision, value),
+        None => "-".to_string(),
+    };
+    let expanded = if row.algorithm.counts_nodes() {
+        format!("{:.1}", row.mean_expanded())
+    } else {
+        "-".to_string()
+    };
+
+    writeln!(
+        out,
+        "| {:>5} | {:<19} | {:<9} | {:>6} | {:>10} | {:>6} | {:>6} | {:>9.2} |",
+        row.depth,
+        row.algorithm.name(),
+        row.heuristic.map_or("-", heuristic_name),
+        format!("{}/{}", row.solved, row.runs),
+        expanded,
+        optional(row.mean_branching(), 2),
+        optional(row.mean_moves(), 1),
+        row.mean_ms()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eight_puzzle::parse_moves;
+
+    #[test]
+    fn test_effective_branching_factor() {
+        // AIMA: 52 nodes at depth 5 give b* = 1.92
+        let b = effective_branching_factor(52, 5).unwrap();
+        assert!((b - 1.92).abs() < 0.01, "b* = {}", b);
+
+        let b = effective_branching_factor(14, 3).unwrap();
+        assert!((b - 2.0).abs() < 1e-9, "b* = {}", b);
+        assert_eq!(None, effective_branching_factor(0, 3));
+        assert_eq!(None, effective_branching_factor(10, 0));
+    }
+
+    #[test]
+    fn test_only_valid_solutions_count() {
+        let board = [1, 2, 3, 7, 4, 5, 0, 8, 6];
+        let moves = |text| parse_moves(text).unwrap();
+
+        assert!(is_valid_solution(board, &moves("URRD"), 4));
+        // a longer path is still a solution
+        assert!(is_valid_solution(board, &moves("URRDLR"), 4));
+        // half of the path, as bidirectional search used to report
+        assert!(!is_valid_solution(board, &moves("UR"), 4));
+        assert!(!is_valid_solution(board, &moves("URRU"), 4));
+        // reaching the goal in fewer moves than the optimum means the depth is wrong
+        assert!(!is_valid_solution(board, &moves("URRD"), 6));
+    }
+
+    #[test]
+    fn test_bench_compares_algorithms_on_groups() {
+        let options = BenchOptions {
+            algorithms: vec![Algorithm::BreadthFirst, Algorithm::AStar],
+            depths: vec![2, 6],
+            boards: 2,
+            ..BenchOptions::default()
+        };
+        let rows = run_bench(&options, &mut |_| Ok(())).unwrap();
+
+        // breadth_first, astar with both heuristics for every depth
+        assert_eq!(6, rows.len());
+        assert!(rows.iter().all(|row| row.solved == 2 && row.runs == 2));
+        assert_eq!(Some(6.0), rows[3].mean_moves());
+        assert_eq!(None, rows[3].heuristic);
+        assert_eq!(Some(Heuristic::ManhattanDistance), rows[4].heuristic);
+        assert!(rows[4].mean_expanded() < rows[3].mean_expanded());
+        assert!(rows[4].mean_branching().unwrap() < rows[3].mean_branching().unwrap());
+    }
+
+    #[test]
+    fn test_bench_prints_a_table() {
+        let options = BenchOptions {
+            algorithms: vec![Algorithm::HillClimbing],
+            heuristics: vec![Heuristic::MisplacedTiles],
+            depths: vec![1],
+            boards: 1,
+            ..BenchOptions::default()
+        };
+        let mut out = vec![];
+        bench(&options, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(
+            "1 boards per depth, seed 42, limit per board of the algorithms counting nodes: 100000 nodes or 1 s",
+            lines[0]
+        );
+        assert!(lines[4].starts_with(
+            "|     1 | hill_climbing       | misplaced |    1/1 |          - |      - |    1.0 |"
+        ));
+        assert!(run_bench(
+            &BenchOptions {
+                depths: vec![40],
+                ..options
+            },
+            &mut |_| Ok(())
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,100 @@
+//! `generate` subcommand
+//! Prints one board per line, so the output can be piped into `batch`;
+//! boards of a random walk or with a known distance get it as a `#` comment.
+
+use std::io::Write;
+
+use crate::cli::args::{BoardSource, GenerateOptions};
+use crate::cli::solve::{format_board, io_error};
+use crate::generator::{random_permutation, random_walk, DistanceOracle};
+use crate::random::Random;
+
+pub fn generate(options: &GenerateOptions, out: &mut dyn Write) -> Result<bool, String> {
+    let mut rng = Random::new(options.seed);
+    let oracle = match options.source {
+        BoardSource::Distance(_) => Some(DistanceOracle::new(options.goal)),
+        _ => None,
+    };
+
+    for _ in 0..options.count {
+        let (board, comment) = match (options.source, oracle.as_ref()) {
+            (BoardSource::Distance(distance), Some(oracle)) => {
+                let board = oracle.random_at(distance, &mut rng).ok_or_else(|| {
+                    format!(
+                        "no board is {} moves from the goal, the farthest are {}",
+                        distance,
+                        oracle.max_distance()
+                    )
+                })?;
+                (board, format!("{} moves", distance))
+            }
+            (BoardSource::Walk(moves), _) => (
+                random_walk(options.goal, moves, &mut rng),
+                format!("walk of {} moves", moves),
+            ),
+            _ => (random_permutation(options.goal, &mut rng), String::new()),
+        };
+
+        if comment.is_empty() {
+            writeln!(out, "{}", format_board(&board))
+        } else {
+            writeln!(out, "{}  # {}", format_board(&board), comment)
+        }
+        .map_err(io_error)?;
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::{parse_board, DEFAULT_SEED};
+    use crate::eight_puzzle::DEFAULT_GOAL;
+
+    fn run(source: BoardSource) -> Result<Vec<String>, String> {
+        let options = GenerateOptions {
+            count: 5,
+            source,
+            goal: DEFAULT_GOAL,
+            seed: DEFAULT_SEED,
+        };
+        let mut out = vec![];
+        generate(&options, &mut out)?;
+
+        Ok(String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect())
+    }
+
+    #[test]
+    fn test_boards_are_reproducible() {
+        let boards = run(BoardSource::Permutation).unwrap();
+
+        assert_eq!(5, boards.len());
+        assert_eq!(boards, run(BoardSource::Permutation).unwrap());
+        assert!(boards.iter().all(|board| parse_board(board).is_ok()));
+    }
+
+    #[test]
+    fn test_boards_at_distance_are_commented() {
+        let boards = run(BoardSource::Distance(20)).unwrap();
+        let oracle = DistanceOracle::new(DEFAULT_GOAL);
+
+        for line in boards.iter() {
+            let (board, comment) = line.split_once('#').unwrap();
+            assert_eq!(" 20 moves", comment);
+            assert_eq!(
+                Some(20),
+                oracle.distance(&parse_board(board.trim()).unwrap())
+            );
+        }
+        assert!(run(BoardSource::Walk(8)).unwrap()[0].ends_with("# walk of 8 moves"));
+        assert_eq!(
+            Err("no board is 32 moves from the goal, the farthest are 31".to_string()),
+            run(BoardSource::Distance(32))
+        );
+    }
+}
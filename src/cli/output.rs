@@ -0,0 +1,301 @@
+//! Output formats of `solve` and `batch`
+//! Besides the text for people there are JSON (an object per board, i.e. JSON Lines in batch),
+//! CSV with a header row and the compact move string of the README, e.g. `URRD`.
+
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crate::cli::args::OutputFormat;
+use crate::eight_puzzle::{format_moves, TileDirection};
+use crate::search::observers::CountingObserver;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Solved(Vec<TileDirection>),
+    /// the search ended without a solution, `true` if it stopped at the node or time limit
+    NotFound(bool),
+    Unsolvable,
+    Invalid(String),
+}
+
+impl Outcome {
+    /// short name used by the machine readable formats
+    pub fn status(&self) -> &'static str {
+        match self {
+            Outcome::Solved(_) => "solved",
+            Outcome::NotFound(false) => "not_found",
+            Outcome::NotFound(true) => "limit_reached",
+            Outcome::Unsolvable => "unsolvable",
+            Outcome::Invalid(_) => "invalid",
+        }
+    }
+
+    pub fn moves(&self) -> &[TileDirection] {
+        match self {
+            Outcome::Solved(moves) => moves,
+            _ => &[],
+        }
+    }
+}
+
+/// result of solving a single board
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// line number in the batch input starting from 1, None for `solve`
+    pub line: Option<usize>,
+    /// board as written by the user
+    pub board: String,
+    pub outcome: Outcome,
+    /// path cost of the solution, 0 without one
+    pub cost: u32,
+    pub counter: CountingObserver,
+    pub elapsed: Duration,
+}
+
+impl Record {
+    fn elapsed_ms(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1000.0
+    }
+}
+
+/// header row of the CSV output, the line column is written only in batch
+pub fn csv_header(with_line: bool) -> String {
+    let columns =
+        "board,status,moves,depth,cost,expanded,generated,pruned,max_depth,elapsed_ms,error";
+    if with_line {
+        format!("line,{}", columns)
+    } else {
+        columns.to_string()
+    }
+}
+
+/// writes a single line of the record in the given format
+pub fn write_record(format: OutputFormat, record: &Record, out: &mut dyn Write) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => write_text(record, out),
+        OutputFormat::Json => writeln!(out, "{}", to_json(record)),
+        OutputFormat::Csv => writeln!(out, "{}", to_csv(record)),
+        OutputFormat::Compact => write_compact(record, out),
+    }
+}
+
+fn write_text(record: &Record, out: &mut dyn Write) -> io::Result<()> {
+    if let Some(line) = record.line {
+        write!(out, "line {}: ", line)?;
+    }
+    write!(out, "{} ", record.board)?;
+
+    match record.outcome {
+        Outcome::Solved(ref moves) => {
+            write!(out, "solved in {} moves", moves.len())?;
+            if !moves.is_empty() {
+                let names: Vec<String> = moves.iter().map(|m| format!("{:?}", m)).collect();
+                write!(out, ": {}", names.join(", "))?;
+            }
+        }
+        Outcome::NotFound(true) => write!(out, "no solution, stopped at the limit")?,
+        Outcome::NotFound(false) => write!(out, "no solution")?,
+        Outcome::Unsolvable => return writeln!(out, "unsolvable"),
+        Outcome::Invalid(ref err) => return writeln!(out, "invalid: {}", err),
+    }
+
+    let counter = &record.counter;
+    writeln!(
+        out,
+        " (expanded {}, generated {}, max depth {}, {:.1} ms)",
+        counter.expanded,
+        counter.generated,
+        counter.max_depth,
+        record.elapsed_ms()
+    )
+}
+
+// moves of a solved board, or the status; batch puts the board in front
+fn write_compact(record: &Record, out: &mut dyn Write) -> io::Result<()> {
+    let mut items = vec![];
+    if record.line.is_some() {
+        items.push(record.board.clone());
+    }
+    match record.outcome {
+        Outcome::Solved(ref moves) if moves.is_empty() => {}
+        Outcome::Solved(ref moves) => items.push(format_moves(moves)),
+        ref outcome => items.push(outcome.status().to_string()),
+    }
+
+    writeln!(out, "{}", items.join(" "))
+}
+
+fn to_json(record: &Record) -> String {
+    let moves = record.outcome.moves();
+    let counter = &record.counter;
+    let mut fields = vec![];
+
+    if let Some(line) = record.line {
+        fields.push(format!("\"line\":{}", line));
+    }
+    fields.push(format!("\"board\":{}", json_string(&record.board)));
+    fields.push(format!("\"status\":\"{}\"", record.outcome.status()));
+    if let Outcome::Solved(_) = record.outcome {
+        fields.push(format!("\"moves\":\"{}\"", format_moves(moves)));
+        fields.push(format!("\"depth\":{}", moves.len()));
+        fields.push(format!("\"cost\":{}", record.cost));
+    }
+    if let Outcome::Invalid(ref err) = record.outcome {
+        fields.push(format!("\"error\":{}", json_string(err)));
+    }
+    fields.push(format!(
+        "\"stats\":{{\"expanded\":{},\"generated\":{},\"pruned\":{},\"max_depth\":{},\"elapsed_ms\":{:.3}}}",
+        counter.expanded,
+        counter.generated,
+        counter.pruned,
+        counter.max_depth,
+        record.elapsed_ms()
+    ));
+
+    format!("{{{}}}", fields.join(","))
+}
+
+fn to_csv(record: &Record) -> String {
+    let moves = record.outcome.moves();
+    let solved = matches!(record.outcome, Outcome::Solved(_));
+    let counter = &record.counter;
+    let mut columns = vec![];
+
+    if let Some(line) = record.line {
+        columns.push(line.to_string());
+    }
+    columns.push(csv_field(&record.board));
+    columns.push(record.outcome.status().to_string());
+    if solved {
+        columns.push(format_moves(moves));
+        columns.push(moves.len().to_string());
+        columns.push(record.cost.to_string());
+    } else {
+        columns.extend(vec![String::new(); 3]);
+    }
+    columns.push(counter.expanded.to_string());
+    columns.push(counter.generated.to_string());
+    columns.push(counter.pruned.to_string());
+    columns.push(counter.max_depth.to_string());
+    columns.push(format!("{:.3}", record.elapsed_ms()));
+    match record.outcome {
+        Outcome::Invalid(ref err) => columns.push(csv_field(err)),
+        _ => columns.push(String::new()),
+    }
+
+    columns.join(",")
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+
+    escaped
+}
+
+// boards contain commas, so fields are quoted when needed
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(outcome: Outcome) -> Record {
+        let mut counter = CountingObserver::new();
+        counter.expanded = 3;
+        counter.generated = 7;
+        counter.max_depth = 2;
+
+        Record {
+            line: Some(4),
+            board: "1,2,3,4,0,5,7,8,6".to_string(),
+            outcome,
+            cost: 2,
+            counter,
+            elapsed: Duration::from_micros(1500),
+        }
+    }
+
+    fn written(format: OutputFormat, record: &Record) -> String {
+        let mut out = vec![];
+        write_record(format, record, &mut out).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_json_of_solved_board() {
+        let solved = record(Outcome::Solved(vec![
+            TileDirection::Right,
+            TileDirection::Down,
+        ]));
+
+        assert_eq!(
+            "{\"line\":4,\"board\":\"1,2,3,4,0,5,7,8,6\",\"status\":\"solved\",\"moves\":\"RD\",\
+             \"depth\":2,\"cost\":2,\"stats\":{\"expanded\":3,\"generated\":7,\"pruned\":0,\
+             \"max_depth\":2,\"elapsed_ms\":1.500}}\n",
+            written(OutputFormat::Json, &solved)
+        );
+    }
+
+    #[test]
+    fn test_json_escapes_errors() {
+        let invalid = record(Outcome::Invalid("\"x\" is not a number".to_string()));
+
+        assert!(written(OutputFormat::Json, &invalid)
+            .contains("\"status\":\"invalid\",\"error\":\"\\\"x\\\" is not a number\""));
+    }
+
+    #[test]
+    fn test_csv_quotes_the_board() {
+        let solved = record(Outcome::Solved(vec![
+            TileDirection::Right,
+            TileDirection::Down,
+        ]));
+        let header = csv_header(true);
+
+        assert_eq!(
+            "4,\"1,2,3,4,0,5,7,8,6\",solved,RD,2,2,3,7,0,2,1.500,\n",
+            written(OutputFormat::Csv, &solved)
+        );
+        assert_eq!(header.split(',').count(), 12);
+        assert!(written(OutputFormat::Csv, &record(Outcome::NotFound(true)))
+            .starts_with("4,\"1,2,3,4,0,5,7,8,6\",limit_reached,,,,3,"));
+    }
+
+    #[test]
+    fn test_compact_prints_moves_or_status() {
+        let mut solved = record(Outcome::Solved(vec![
+            TileDirection::Right,
+            TileDirection::Down,
+        ]));
+
+        assert_eq!(
+            "1,2,3,4,0,5,7,8,6 RD\n",
+            written(OutputFormat::Compact, &solved)
+        );
+        solved.line = None;
+        assert_eq!("RD\n", written(OutputFormat::Compact, &solved));
+        assert_eq!(
+            "1,2,3,4,0,5,7,8,6 unsolvable\n",
+            written(OutputFormat::Compact, &record(Outcome::Unsolvable))
+        );
+    }
+}
@@ -0,0 +1,453 @@
+//! `play` subcommand
+//! The player moves the blank with the arrow keys or WASD on a board drawn with ANSI escape codes.
+//! A hint and the auto-solve ask A* with Manhattan distance, so they always follow an optimal path.
+//! The terminal is switched to reading single key presses with `stty`, which keeps the crate
+//! free of dependencies; without a terminal the keys are read from standard input as they come.
+
+use std::fs::File;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+use crate::cli::args::PlayOptions;
+use crate::cli::solve::{format_board, io_error};
+use crate::eight_puzzle::{
+    EightPuzzle, EightPuzzleState, PuzzleStateRow, TileDirection, DEFAULT_GOAL,
+};
+use crate::generator::random_permutation;
+use crate::random::Random;
+use crate::search::informed::astar_search;
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+const HELP: &str = "arrows or WASD move the blank, u undo, h hint, x solve, q quit";
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Key {
+    Move(TileDirection),
+    Undo,
+    Hint,
+    Solve,
+    Quit,
+    Other,
+}
+
+/// reads key presses from the bytes of the terminal, arrows come as `ESC [ A` to `ESC [ D`
+pub struct KeyReader<'a> {
+    input: &'a mut dyn Read,
+}
+
+impl<'a> KeyReader<'a> {
+    pub fn new(input: &'a mut dyn Read) -> Self {
+        KeyReader { input }
+    }
+
+    /// None at the end of the input
+    pub fn next_key(&mut self) -> io::Result<Option<Key>> {
+        let byte = match self.next_byte()? {
+            Some(byte) => byte,
+            None => return Ok(None),
+        };
+
+        let key = match byte.to_ascii_lowercase() {
+            b'w' => Key::Move(TileDirection::Up),
+            b's' => Key::Move(TileDirection::Down),
+            b'a' => Key::Move(TileDirection::Left),
+            b'd' => Key::Move(TileDirection::Right),
+            b'u' => Key::Undo,
+            b'h' => Key::Hint,
+            b'x' => Key::Solve,
+            // Ctrl-C and Ctrl-D do not send signals in the raw terminal
+            b'q' | 3 | 4 => Key::Quit,
+            0x1b => self.escape_sequence()?,
+            _ => Key::Other,
+        };
+
+        Ok(Some(key))
+    }
+
+    fn escape_sequence(&mut self) -> io::Result<Key> {
+        if !matches!(self.next_byte()?, Some(b'[') | Some(b'O')) {
+            return Ok(Key::Other);
+        }
+
+        Ok(match self.next_byte()? {
+            Some(b'A') => Key::Move(TileDirection::Up),
+            Some(b'B') => Key::Move(TileDirection::Down),
+            Some(b'C') => Key::Move(TileDirection::Right),
+            Some(b'D') => Key::Move(TileDirection::Left),
+            _ => Key::Other,
+        })
+    }
+
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        let mut byte = [0];
+        match self.input.read(&mut byte)? {
+            0 => Ok(None),
+            _ => Ok(Some(byte[0])),
+        }
+    }
+}
+
+/// state of a game: the board, the moves for undo and the last message for the player
+pub struct Game {
+    state: EightPuzzleState,
+    goal: EightPuzzleState,
+    history: Vec<EightPuzzleState>,
+    message: String,
+}
+
+impl Game {
+    pub fn new(board: PuzzleStateRow, goal: PuzzleStateRow) -> Result<Self, String> {
+        let state = EightPuzzleState::new(board);
+        let goal = EightPuzzleState::new(goal);
+        if !state.can_reach(&goal) {
+            return Err(format!("{} can not reach the goal", format_board(&board)));
+        }
+
+        Ok(Game {
+            state,
+            goal,
+            history: vec![],
+            message: String::new(),
+        })
+    }
+
+    pub fn state(&self) -> &EightPuzzleState {
+        &self.state
+    }
+
+    pub fn moves(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.state.value() == self.goal.value()
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// returns false if the blank can not move in the direction
+    pub fn move_blank(&mut self, direction: TileDirection) -> bool {
+        if !self.state.possible_actions().contains(&direction) {
+            self.message = format!("the blank can not move {:?}", direction);
+            return false;
+        }
+
+        let next = self.state.next_state(&direction);
+        self.history.push(std::mem::replace(&mut self.state, next));
+        self.message.clear();
+        true
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(previous) => {
+                self.state = previous;
+                self.message.clear();
+                true
+            }
+            None => {
+                self.message = "nothing to undo".to_string();
+                false
+            }
+        }
+    }
+
+    /// optimal moves from the current board to the goal
+    pub fn solution(&self) -> Vec<TileDirection> {
+        let puzzle = EightPuzzle::new(self.state.clone()).with_goal(*self.goal.value());
+
+        astar_search(Box::new(puzzle))
+            .map(|node| {
+                node.solution()[1..]
+                    .iter()
+                    .map(TileDirection::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// next optimal move, also shown in the message
+    pub fn hint(&mut self) -> Option<TileDirection> {
+        let solution = self.solution();
+        self.message = match solution.first() {
+            Some(direction) => format!(
+                "hint: move the blank {:?}, {} moves to go",
+                direction,
+                solution.len()
+            ),
+            None => "already solved".to_string(),
+        };
+
+        solution.first().copied()
+    }
+}
+
+/// plays on the terminal until the board is solved or the player quits
+pub fn play(
+    options: &PlayOptions,
+    input: &mut dyn Read,
+    out: &mut dyn Write,
+) -> Result<bool, String> {
+    let board = match options.board {
+        Some(board) => board,
+        None => {
+            let mut rng = options.seed.map_or_else(Random::from_time, Random::new);
+            let mut board = options.goal;
+            while board == options.goal {
+                board = random_permutation(options.goal, &mut rng);
+            }
+            board
+        }
+    };
+    let mut game = Game::new(board, options.goal)?;
+    let mut keys = KeyReader::new(input);
+
+    write!(out, "{}", HIDE_CURSOR).map_err(io_error)?;
+    draw(&game, out).map_err(io_error)?;
+
+    while !game.is_solved() {
+        let key = match keys.next_key().map_err(io_error)? {
+            Some(key) => key,
+            None => break,
+        };
+
+        match key {
+            Key::Move(direction) => {
+                game.move_blank(direction);
+            }
+            Key::Undo => {
+                game.undo();
+            }
+            Key::Hint => {
+                game.hint();
+            }
+            Key::Solve => {
+                for direction in game.solution() {
+                    thread::sleep(options.delay);
+                    game.move_blank(direction);
+                    draw(&game, out).map_err(io_error)?;
+                }
+                continue;
+            }
+            Key::Quit => break,
+            Key::Other => continue,
+        }
+        draw(&game, out).map_err(io_error)?;
+    }
+
+    if game.is_solved() {
+        writeln!(out, "Solved in {} moves!", game.moves()).map_err(io_error)?;
+    }
+    write!(out, "{}", SHOW_CURSOR).map_err(io_error)?;
+
+    Ok(true)
+}
+
+/// plays with the keys of the terminal if standard input is one
+pub fn play_in_terminal(options: &PlayOptions, out: &mut dyn Write) -> Result<bool, String> {
+    let stdin = io::stdin();
+    let _raw_mode = if stdin.is_terminal() {
+        Some(RawMode::enable()?)
+    } else {
+        None
+    };
+
+    play(options, &mut stdin.lock(), out)
+}
+
+fn draw(game: &Game, out: &mut dyn Write) -> io::Result<()> {
+    write!(out, "{}", CLEAR_SCREEN)?;
+    writeln!(out, "8-puzzle, moves: {}", game.moves())?;
+
+    let goal = game.goal.value();
+    let line = |left: &str, middle: &str, right: &str| {
+        format!("{}───{}───{}───{}", left, middle, middle, right)
+    };
+    writeln!(out, "{}", line("┌", "┬", "┐"))?;
+    for (row, tiles) in game.state.value().chunks(3).enumerate() {
+        write!(out, "│")?;
+        for (column, &tile) in tiles.iter().enumerate() {
+            match tile {
+                0 => write!(out, "   │")?,
+                // tiles already at their goal square are green
+                tile if goal[row * 3 + column] == tile => {
+                    write!(out, " {}{}{} │", GREEN, tile, RESET)?
+                }
+                tile => write!(out, " {} │", tile)?,
+            }
+        }
+        writeln!(out)?;
+        if row < 2 {
+            writeln!(out, "{}", line("├", "┼", "┤"))?;
+        }
+    }
+    writeln!(out, "{}", line("└", "┴", "┘"))?;
+
+    if *goal != DEFAULT_GOAL {
+        writeln!(out, "goal: {}", format_board(goal))?;
+    }
+    writeln!(out, "{}", HELP)?;
+    writeln!(out, "{}", game.message)?;
+    out.flush()
+}
+
+/// terminal reading key presses without echo until dropped; Ctrl-C comes as a key,
+/// so the settings are always restored
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enable() -> Result<Self, String> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+
+        Ok(RawMode {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> Result<String, String> {
+    let tty = File::open("/dev/tty").map_err(|err| format!("failed to open terminal: {}", err))?;
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::from(tty))
+        .output()
+        .map_err(|err| format!("failed to run stty: {}", err))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "stty failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn keys(input: &str) -> Vec<Key> {
+        let mut bytes = input.as_bytes();
+        let mut reader = KeyReader::new(&mut bytes);
+        let mut keys = vec![];
+        while let Some(key) = reader.next_key().unwrap() {
+            keys.push(key);
+        }
+
+        keys
+    }
+
+    fn options(board: PuzzleStateRow) -> PlayOptions {
+        PlayOptions {
+            board: Some(board),
+            goal: DEFAULT_GOAL,
+            seed: None,
+            delay: Duration::from_millis(0),
+        }
+    }
+
+    #[test]
+    fn test_arrows_and_wasd() {
+        assert_eq!(
+            vec![
+                Key::Move(TileDirection::Up),
+                Key::Move(TileDirection::Down),
+                Key::Move(TileDirection::Right),
+                Key::Move(TileDirection::Left),
+                Key::Move(TileDirection::Up),
+                Key::Move(TileDirection::Right),
+                Key::Undo,
+                Key::Hint,
+                Key::Solve,
+                Key::Other,
+                Key::Quit,
+            ],
+            keys("\x1b[A\x1b[B\x1b[C\x1b[DWduhx\nq")
+        );
+    }
+
+    #[test]
+    fn test_moves_and_undo() {
+        let mut game = Game::new([1, 2, 3, 4, 0, 5, 7, 8, 6], DEFAULT_GOAL).unwrap();
+
+        assert!(game.move_blank(TileDirection::Right));
+        assert!(!game.move_blank(TileDirection::Right));
+        assert_eq!("the blank can not move Right", game.message());
+        assert_eq!(1, game.moves());
+        assert!(game.undo());
+        assert!(!game.undo());
+        assert_eq!(&[1, 2, 3, 4, 0, 5, 7, 8, 6], game.state().value());
+        assert!(Game::new([2, 1, 3, 4, 5, 6, 7, 8, 0], DEFAULT_GOAL).is_err());
+    }
+
+    #[test]
+    fn test_hint_is_the_next_optimal_move() {
+        let mut game = Game::new([1, 2, 3, 4, 0, 5, 7, 8, 6], DEFAULT_GOAL).unwrap();
+
+        assert_eq!(Some(TileDirection::Right), game.hint());
+        assert_eq!("hint: move the blank Right, 2 moves to go", game.message());
+    }
+
+    #[test]
+    fn test_player_solves_the_board() {
+        let mut out = vec![];
+        play(
+            &options([1, 2, 3, 4, 0, 5, 7, 8, 6]),
+            &mut "ddud\x1b[B".as_bytes(),
+            &mut out,
+        )
+        .unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.contains("the blank can not move Right"));
+        assert!(text.ends_with("Solved in 2 moves!\n\x1b[?25h"));
+    }
+
+    #[test]
+    fn test_auto_solve_animates_every_move() {
+        let mut out = vec![];
+        play(
+            &options([1, 2, 3, 7, 4, 5, 0, 8, 6]),
+            &mut "x".as_bytes(),
+            &mut out,
+        )
+        .unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        // the first board and one for each of the 4 moves
+        assert_eq!(5, text.matches(CLEAR_SCREEN).count());
+        assert!(text.contains("8-puzzle, moves: 4"));
+        assert!(text.contains("Solved in 4 moves!"));
+    }
+
+    #[test]
+    fn test_quit_and_random_board() {
+        let mut out = vec![];
+        let mut options = options(DEFAULT_GOAL);
+        options.board = None;
+        options.seed = Some(5);
+
+        assert_eq!(Ok(true), play(&options, &mut "q".as_bytes(), &mut out));
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains(HELP));
+        assert!(!text.contains("Solved"));
+    }
+}
@@ -0,0 +1,250 @@
+//! Boards along a solution drawn as grids
+//! The tile that moved into the blank is marked with brackets, e.g. `[5]`. Side by side
+//! rendering wraps the boards into rows of `LINE_WIDTH` characters; the animation redraws
+//! a single board in place with ANSI escape codes, so the text above it stays on the screen.
+
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::cli::args::{GridStyle, RenderMode};
+use crate::eight_puzzle::{EightPuzzleState, PuzzleStateRow, TileDirection};
+
+const LINE_WIDTH: usize = 80;
+const GAP: &str = "   ";
+// 3 cells of 3 characters and 4 borders
+const GRID_WIDTH: usize = 13;
+
+/// a board with the label drawn above it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub label: String,
+    pub board: PuzzleStateRow,
+    /// square of the tile that moved into the blank
+    pub moved: Option<usize>,
+}
+
+/// the board and the board after every move
+pub fn frames(board: PuzzleStateRow, moves: &[TileDirection]) -> Vec<Frame> {
+    let mut state = EightPuzzleState::new(board);
+    let mut frames = vec![Frame {
+        label: "start".to_string(),
+        board,
+        moved: None,
+    }];
+
+    for (i, direction) in moves.iter().enumerate() {
+        // the tile takes the square the blank leaves
+        let blank = state.value().iter().position(|&tile| tile == 0);
+        state = state.next_state(direction);
+        frames.push(Frame {
+            label: format!("{}: {:?}", i + 1, direction),
+            board: *state.value(),
+            moved: blank,
+        });
+    }
+
+    frames
+}
+
+/// lines of the board drawn as a grid, all of them `GRID_WIDTH` characters wide
+pub fn grid(board: &PuzzleStateRow, moved: Option<usize>, style: GridStyle) -> Vec<String> {
+    let (top, middle, bottom, wall) = match style {
+        GridStyle::Ascii => (["+", "+", "+"], ["+", "+", "+"], ["+", "+", "+"], "|"),
+        GridStyle::Unicode => (["┌", "┬", "┐"], ["├", "┼", "┤"], ["└", "┴", "┘"], "│"),
+    };
+    let horizontal = match style {
+        GridStyle::Ascii => "---",
+        GridStyle::Unicode => "───",
+    };
+    let border = |[left, cross, right]: [&str; 3]| {
+        format!(
+            "{}{}{}{}{}{}{}",
+            left, horizontal, cross, horizontal, cross, horizontal, right
+        )
+    };
+
+    let mut lines = vec![border(top)];
+    for (row, tiles) in board.chunks(3).enumerate() {
+        let mut line = wall.to_string();
+        for (column, &tile) in tiles.iter().enumerate() {
+            let cell = match tile {
+                0 => "   ".to_string(),
+                tile if moved == Some(row * 3 + column) => format!("[{}]", tile),
+                tile => format!(" {} ", tile),
+            };
+            line.push_str(&cell);
+            line.push_str(wall);
+        }
+        lines.push(line);
+        lines.push(border(if row < 2 { middle } else { bottom }));
+    }
+
+    lines
+}
+
+/// draws the boards after the board moves by moves in the given mode
+pub fn render_solution(
+    mode: RenderMode,
+    style: GridStyle,
+    delay: Duration,
+    board: PuzzleStateRow,
+    moves: &[TileDirection],
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let frames = frames(board, moves);
+
+    match mode {
+        RenderMode::SideBySide => write_side_by_side(&frames, style, out),
+        RenderMode::Animate => animate(&frames, style, delay, out),
+    }
+}
+
+pub fn write_side_by_side(
+    frames: &[Frame],
+    style: GridStyle,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let per_row = ((LINE_WIDTH + GAP.len()) / (GRID_WIDTH + GAP.len())).max(1);
+
+    for (i, chunk) in frames.chunks(per_row).enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        let labels: Vec<String> = chunk
+            .iter()
+            .map(|frame| format!("{:<width$}", frame.label, width = GRID_WIDTH))
+            .collect();
+        writeln!(out, "{}", labels.join(GAP).trim_end())?;
+
+        let grids: Vec<Vec<String>> = chunk
+            .iter()
+            .map(|frame| grid(&frame.board, frame.moved, style))
+            .collect();
+        for line in 0..grids[0].len() {
+            let parts: Vec<&str> = grids.iter().map(|lines| lines[line].as_str()).collect();
+            writeln!(out, "{}", parts.join(GAP))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// draws every frame over the previous one, waiting `delay` in between
+pub fn animate(
+    frames: &[Frame],
+    style: GridStyle,
+    delay: Duration,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    for (i, frame) in frames.iter().enumerate() {
+        let lines = grid(&frame.board, frame.moved, style);
+        if i > 0 {
+            thread::sleep(delay);
+            // back to the label line of the previous frame
+            write!(out, "\x1b[{}A", lines.len() + 1)?;
+        }
+        // the label is cleared to the end of line as labels differ in length
+        writeln!(out, "{}\x1b[K", frame.label)?;
+        for line in lines.iter() {
+            writeln!(out, "{}", line)?;
+        }
+        out.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: PuzzleStateRow = [1, 2, 3, 4, 0, 5, 7, 8, 6];
+
+    fn rendered(mode: RenderMode, style: GridStyle, moves: &[TileDirection]) -> String {
+        let mut out = vec![];
+        render_solution(
+            mode,
+            style,
+            Duration::from_millis(0),
+            BOARD,
+            moves,
+            &mut out,
+        )
+        .unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_frames_mark_the_moved_tile() {
+        let frames = frames(BOARD, &[TileDirection::Right, TileDirection::Down]);
+
+        assert_eq!(3, frames.len());
+        assert_eq!(None, frames[0].moved);
+        assert_eq!("1: Right", frames[1].label);
+        assert_eq!(Some(4), frames[1].moved);
+        assert_eq!([1, 2, 3, 4, 5, 6, 7, 8, 0], frames[2].board);
+        assert_eq!(Some(5), frames[2].moved);
+    }
+
+    #[test]
+    fn test_ascii_grid() {
+        assert_eq!(
+            vec![
+                "+---+---+---+",
+                "| 1 | 2 | 3 |",
+                "+---+---+---+",
+                "| 4 |[5]|   |",
+                "+---+---+---+",
+                "| 7 | 8 | 6 |",
+                "+---+---+---+",
+            ],
+            grid(&[1, 2, 3, 4, 5, 0, 7, 8, 6], Some(4), GridStyle::Ascii)
+        );
+    }
+
+    #[test]
+    fn test_unicode_grid_has_the_same_width() {
+        let lines = grid(&BOARD, None, GridStyle::Unicode);
+
+        assert_eq!("┌───┬───┬───┐", lines[0]);
+        assert_eq!("│ 4 │   │ 5 │", lines[3]);
+        assert!(lines.iter().all(|line| line.chars().count() == GRID_WIDTH));
+    }
+
+    #[test]
+    fn test_side_by_side_wraps_rows() {
+        let text = rendered(
+            RenderMode::SideBySide,
+            GridStyle::Ascii,
+            &[TileDirection::Right, TileDirection::Down],
+        );
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(8, lines.len());
+        assert_eq!("start           1: Right        2: Down", lines[0]);
+        assert_eq!("| 4 |   | 5 |   | 4 |[5]|   |   | 4 | 5 |[6]|", lines[4]);
+
+        // 5 boards fit in 80 characters, the 6th starts a new row
+        let moves = [TileDirection::Right, TileDirection::Left].repeat(3);
+        let text = rendered(RenderMode::SideBySide, GridStyle::Unicode, &moves[..5]);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(17, lines.len());
+        assert!(lines.iter().all(|line| line.chars().count() <= LINE_WIDTH));
+        assert_eq!("5: Right", lines[9]);
+    }
+
+    #[test]
+    fn test_animation_redraws_in_place() {
+        let text = rendered(
+            RenderMode::Animate,
+            GridStyle::Ascii,
+            &[TileDirection::Right, TileDirection::Down],
+        );
+
+        assert_eq!(2, text.matches("\x1b[8A").count());
+        assert!(text.starts_with("start\x1b[K\n+---+---+---+\n"));
+        assert!(text.ends_with("2: Down\x1b[K\n+---+---+---+\n| 1 | 2 | 3 |\n+---+---+---+\n| 4 | 5 |[6]|\n+---+---+---+\n| 7 | 8 |   |\n+---+---+---+\n"));
+    }
+}
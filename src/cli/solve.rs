@@ -0,0 +1,470 @@
+//! `solve` and `check` subcommands
+
+use std::io::Write;
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::cli::args::{Algorithm, CheckOptions, OutputFormat, SolveOptions};
+use crate::cli::output::{csv_header, write_record, Outcome, Record};
+use crate::cli::render::render_solution;
+use crate::eight_puzzle::{EightPuzzle, EightPuzzleState, TileDirection, DEFAULT_GOAL};
+use crate::genetic::{genetic_algorithm, GeneticConfig};
+use crate::random::Random;
+use crate::search::graphviz::{DotMode, DotRecorder, DEFAULT_MAX_NODES};
+use crate::search::informed::{astar_search_observed, beam_search_observed};
+use crate::search::local::{simulated_annealing, steepest_ascent_hill_climbing, CoolingSchedule};
+use crate::search::observers::{CountingObserver, LimitObserver, SearchObserver};
+use crate::search::uninformed::*;
+use crate::search::{SearchNode, SearchProblem};
+
+// how many moves over a plateau hill climbing makes before giving up
+const MAX_SIDEWAYS_MOVES: usize = 100;
+const ANNEALING_MAX_STEPS: usize = 100_000;
+// number of nodes beam search keeps on every level
+const BEAM_WIDTH: usize = 100;
+
+/// result of a single run, shared by all the output formats
+pub struct SolveReport {
+    pub solution: Option<SearchNode>,
+    pub counter: CountingObserver,
+    /// lines describing how a local search or the genetic algorithm stopped
+    pub notes: Vec<String>,
+    /// the search gave up at the node or time limit
+    pub limit_reached: bool,
+}
+
+impl SolveReport {
+    /// moves of the solution, without the root
+    pub fn moves(&self) -> Vec<TileDirection> {
+        self.solution
+            .as_ref()
+            .map(|node| {
+                node.solution()[1..]
+                    .iter()
+                    .map(TileDirection::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        match self.solution {
+            Some(_) => Outcome::Solved(self.moves()),
+            None => Outcome::NotFound(self.limit_reached),
+        }
+    }
+
+    /// path cost of the solution, 0 without one
+    pub fn cost(&self) -> u32 {
+        self.solution.as_ref().map_or(0, SearchNode::path_cost)
+    }
+}
+
+/// returns true if a solution was found
+pub fn solve(options: &SolveOptions, out: &mut dyn Write) -> Result<bool, String> {
+    let started = Instant::now();
+    let mut record = Record {
+        line: None,
+        board: format_board(&options.board),
+        outcome: Outcome::Unsolvable,
+        cost: 0,
+        counter: CountingObserver::new(),
+        elapsed: Default::default(),
+    };
+    if options.format == OutputFormat::Csv {
+        writeln!(out, "{}", csv_header(false)).map_err(io_error)?;
+    }
+
+    let initial_state = EightPuzzleState::new(options.board);
+    if !initial_state.can_reach(&EightPuzzleState::new(options.goal)) {
+        match options.format {
+            OutputFormat::Text => writeln!(out, "Unsolvable problem: {:?}", options.board),
+            format => write_record(format, &record, out),
+        }
+        .map_err(io_error)?;
+        return Ok(false);
+    }
+
+    let mut recorder = options
+        .dot
+        .as_ref()
+        .map(|_| DotRecorder::new(DotMode::Tree, DEFAULT_MAX_NODES));
+    let report = run_search(options, &mut recorder)?;
+
+    match options.format {
+        OutputFormat::Text => write_text(options, &report, out).and_then(|_| {
+            match (options.render, report.solution.is_some()) {
+                (Some(mode), true) => render_solution(
+                    mode,
+                    options.grid,
+                    options.delay,
+                    options.board,
+                    &report.moves(),
+                    out,
+                ),
+                _ => Ok(()),
+            }
+        }),
+        format => {
+            record.outcome = report.outcome();
+            record.cost = report.cost();
+            record.counter = report.counter.clone();
+            record.elapsed = started.elapsed();
+            write_record(format, &record, out)
+        }
+    }
+    .map_err(io_error)?;
+
+    if let (Some(path), Some(recorder)) = (options.dot.as_ref(), recorder) {
+        recorder
+            .save(path, report.solution.as_ref())
+            .map_err(|err| format!("failed to write {}: {}", path, err))?;
+        // the other formats are read by programs, so they get only the record
+        if options.format == OutputFormat::Text {
+            writeln!(
+                out,
+                "Search tree with {} nodes saved into {}",
+                recorder.len(),
+                path
+            )
+            .map_err(io_error)?;
+        }
+    }
+
+    Ok(report.solution.is_some())
+}
+
+/// runs the algorithm of the options, recording the search tree when a recorder is given
+pub fn run_search(
+    options: &SolveOptions,
+    recorder: &mut Option<DotRecorder>,
+) -> Result<SolveReport, String> {
+    let puzzle = EightPuzzle::from_row(options.board)
+        .with_goal(options.goal)
+        .with_heuristic(options.heuristic);
+    let limit = LimitObserver::new(options.max_nodes, options.timeout);
+    let mut observer = ((CountingObserver::new(), limit), recorder.take());
+    let mut notes = vec![];
+
+    let solution = match options.algorithm {
+        Algorithm::DepthFirst => depth_first_search_observed(Box::new(puzzle), &mut observer),
+        Algorithm::BreadthFirst => breadth_first_search_observed(Box::new(puzzle), &mut observer),
+        Algorithm::UniformCost => uniform_cost_search_observed(Box::new(puzzle), &mut observer),
+        Algorithm::DepthLimited => {
+            depth_limited_search_observed(Box::new(puzzle), options.depth, &mut observer)
+        }
+        Algorithm::IterativeDeepening => {
+            iterative_deepening_search_observed(Box::new(puzzle), &mut observer)
+        }
+        Algorithm::Bidirectional => {
+            let goal_problem = puzzle.reverse_problem();
+            let linked_problem = goal_problem.reverse_problem();
+            bidirectional_search_observed(Box::new(puzzle), Box::new(goal_problem), &mut observer)
+                .map(|(front, back)| link_paths(Box::new(linked_problem), &front, &back))
+        }
+        Algorithm::AStar => astar_search_observed(Box::new(puzzle), &mut observer),
+        Algorithm::Beam => beam_search_observed(Box::new(puzzle), BEAM_WIDTH, &mut observer),
+        Algorithm::Genetic => {
+            if options.goal != DEFAULT_GOAL {
+                return Err("genetic algorithm supports only the default goal".to_string());
+            }
+            let config = GeneticConfig {
+                heuristic: options.heuristic,
+                seed: options.seed,
+                ..GeneticConfig::default()
+            };
+            let res = genetic_algorithm(puzzle.state(), &config);
+            notes.push(format!(
+                "Genetic algorithm stopped after {} generations, best fitness {}",
+                res.generations(),
+                res.best.fitness
+            ));
+            Some(replay(Box::new(puzzle), &res.best.solution)).filter(|node| node.is_goal())
+        }
+        Algorithm::HillClimbing => {
+            let res = steepest_ascent_hill_climbing(Box::new(puzzle), MAX_SIDEWAYS_MOVES);
+            notes.push(format!(
+                "Hill climbing stopped at {:?} after {} moves, value {}",
+                res.outcome,
+                res.steps,
+                res.value()
+            ));
+            Some(res.node).filter(|node| node.is_goal())
+        }
+        Algorithm::SimulatedAnnealing => {
+            let mut rng = Random::new(options.seed);
+            let schedule = CoolingSchedule::default();
+            let res =
+                simulated_annealing(Box::new(puzzle), schedule, ANNEALING_MAX_STEPS, &mut rng);
+            notes.push(format!(
+                "Simulated annealing stopped after {} steps, accepted {:.1}% moves, value {}",
+                res.steps(),
+                res.acceptance_rate() * 100.0,
+                res.value()
+            ));
+            Some(res.node).filter(|node| node.is_goal())
+        }
+    };
+
+    let ((counter, limit), used_recorder) = observer;
+    *recorder = used_recorder;
+    let limit_reached = solution.is_none() && limit.should_stop();
+
+    Ok(SolveReport {
+        solution,
+        counter,
+        notes,
+        limit_reached,
+    })
+}
+
+// builds search node by applying moves one by one from the initial state
+fn replay(puzzle: Box<dyn SearchProblem>, moves: &[TileDirection]) -> SearchNode {
+    let mut node = SearchNode::root(puzzle);
+    for &direction in moves.iter() {
+        node = SearchNode::child_node(&Rc::new(node), direction.into());
+    }
+
+    node
+}
+
+// the path of the search from the board followed by the path of the search from the goal
+// walked back, every move of the latter undone by the opposite one
+fn link_paths(puzzle: Box<dyn SearchProblem>, front: &SearchNode, back: &SearchNode) -> SearchNode {
+    let mut moves: Vec<TileDirection> = front.solution()[1..]
+        .iter()
+        .map(TileDirection::from)
+        .collect();
+    moves.extend(
+        back.solution()[1..]
+            .iter()
+            .rev()
+            .map(|action| TileDirection::from(action).opposite()),
+    );
+
+    replay(puzzle, &moves)
+}
+
+fn write_text(
+    options: &SolveOptions,
+    report: &SolveReport,
+    out: &mut dyn Write,
+) -> std::io::Result<()> {
+    writeln!(out, "Going to use {}", options.algorithm.name())?;
+    for note in report.notes.iter() {
+        writeln!(out, "{}", note)?;
+    }
+    if report.limit_reached {
+        writeln!(out, "Search stopped at the node or time limit")?;
+    }
+
+    match report.solution {
+        None => writeln!(out, "no solution for {:?}", options.board)?,
+        Some(ref node) => {
+            writeln!(out, "Found solution after {:?} steps. Path:", node.depth())?;
+            for action in node.solution().iter() {
+                match TileDirection::from(action) {
+                    TileDirection::None => write!(out, "|-> ")?,
+                    direction => write!(out, "{:?}, ", direction)?,
+                }
+            }
+            writeln!(out, "|")?;
+        }
+    }
+
+    let counter = &report.counter;
+    writeln!(
+        out,
+        "Expanded {} nodes, generated {}, pruned {} duplicates, max depth {}",
+        counter.expanded, counter.generated, counter.pruned, counter.max_depth
+    )
+}
+
+/// returns true if the goal can be reached from the board
+pub fn check(options: &CheckOptions, out: &mut dyn Write) -> Result<bool, String> {
+    let solvable =
+        EightPuzzleState::new(options.board).can_reach(&EightPuzzleState::new(options.goal));
+
+    if solvable {
+        writeln!(out, "{:?} is solvable", options.board).map_err(io_error)?;
+    } else {
+        writeln!(out, "{:?} is not solvable", options.board).map_err(io_error)?;
+    }
+
+    Ok(solvable)
+}
+
+/// board in the form it is given on the command line
+pub(crate) fn format_board(board: &[u8]) -> String {
+    let numbers: Vec<String> = board.iter().map(u8::to_string).collect();
+    numbers.join(",")
+}
+
+pub(crate) fn io_error(err: std::io::Error) -> String {
+    match err.kind() {
+        std::io::ErrorKind::BrokenPipe => crate::cli::OUTPUT_CLOSED.to_string(),
+        _ => format!("failed to write output: {}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::{GridStyle, RenderMode, SolveOptions};
+    use crate::eight_puzzle::{format_moves, parse_moves};
+
+    fn run(options: &SolveOptions) -> (bool, String) {
+        let mut out = vec![];
+        let found = solve(options, &mut out).unwrap();
+
+        (found, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_solve_prints_the_path() {
+        let (found, text) = run(&SolveOptions::new([1, 2, 3, 4, 5, 0, 7, 8, 6]));
+
+        assert!(found);
+        assert!(text.contains("Going to use breadth_first"));
+        assert!(text.contains("Found solution after 1 steps. Path:\n|-> Down, |"));
+    }
+
+    #[test]
+    fn test_solve_respects_the_goal() {
+        let mut options = SolveOptions::new([1, 0, 2, 3, 4, 5, 6, 7, 8]);
+        options.goal = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+        options.algorithm = Algorithm::AStar;
+        let (found, text) = run(&options);
+
+        assert!(found);
+        assert!(text.contains("|-> Left, |"));
+    }
+
+    #[test]
+    fn test_node_limit_stops_the_search() {
+        for algorithm in [Algorithm::BreadthFirst, Algorithm::Beam] {
+            let mut options = SolveOptions::new([8, 6, 7, 2, 5, 4, 3, 0, 1]);
+            options.algorithm = algorithm;
+            options.max_nodes = Some(20);
+            let (found, text) = run(&options);
+
+            assert!(!found);
+            assert!(text.contains("Search stopped at the node or time limit"));
+            assert!(text.contains("Expanded 20 nodes"));
+        }
+    }
+
+    #[test]
+    fn test_machine_readable_formats() {
+        let mut options = SolveOptions::new([1, 2, 3, 4, 0, 5, 7, 8, 6]);
+        options.format = OutputFormat::Compact;
+        assert_eq!((true, "RD\n".to_string()), run(&options));
+
+        options.format = OutputFormat::Json;
+        let (_, json) = run(&options);
+        assert!(json.starts_with(
+            "{\"board\":\"1,2,3,4,0,5,7,8,6\",\"status\":\"solved\",\"moves\":\"RD\",\"depth\":2,\"cost\":2,"
+        ));
+
+        options.format = OutputFormat::Csv;
+        let (_, csv) = run(&options);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(csv_header(false), lines[0]);
+        assert!(lines[1].starts_with("\"1,2,3,4,0,5,7,8,6\",solved,RD,2,2,"));
+
+        options.board = [2, 1, 3, 4, 5, 6, 7, 8, 0];
+        options.format = OutputFormat::Compact;
+        assert_eq!((false, "unsolvable\n".to_string()), run(&options));
+    }
+
+    #[test]
+    fn test_compact_output_replays_to_the_goal() {
+        let board = [8, 6, 7, 2, 5, 4, 3, 0, 1];
+        let mut options = SolveOptions::new(board);
+        options.algorithm = Algorithm::AStar;
+        options.format = OutputFormat::Compact;
+        let (_, moves) = run(&options);
+
+        let moves = parse_moves(&moves).unwrap();
+        let state = moves
+            .iter()
+            .fold(EightPuzzleState::new(board), |state, m| state.next_state(m));
+        assert_eq!(31, moves.len());
+        assert_eq!(&DEFAULT_GOAL, state.value());
+    }
+
+    #[test]
+    fn test_bidirectional_links_both_halves() {
+        for (board, goal) in [
+            ([1, 2, 3, 7, 4, 5, 0, 8, 6], DEFAULT_GOAL),
+            ([8, 6, 7, 2, 5, 4, 3, 0, 1], DEFAULT_GOAL),
+            ([1, 2, 0, 3, 4, 5, 6, 7, 8], [0, 1, 2, 3, 4, 5, 6, 7, 8]),
+        ] {
+            let mut options = SolveOptions::new(board);
+            options.algorithm = Algorithm::Bidirectional;
+            options.goal = goal;
+            let report = run_search(&options, &mut None).unwrap();
+
+            let moves = report.moves();
+            let state = moves
+                .iter()
+                .fold(EightPuzzleState::new(board), |state, m| state.next_state(m));
+            assert_eq!(&goal, state.value(), "{}", format_moves(&moves));
+            assert_eq!(moves.len() as u32, report.cost());
+        }
+
+        let mut options = SolveOptions::new([1, 2, 3, 7, 4, 5, 0, 8, 6]);
+        options.algorithm = Algorithm::Bidirectional;
+        options.format = OutputFormat::Compact;
+        assert_eq!((true, "URRD\n".to_string()), run(&options));
+    }
+
+    #[test]
+    fn test_solution_is_rendered_after_the_text() {
+        let mut options = SolveOptions::new([1, 2, 3, 4, 0, 5, 7, 8, 6]);
+        options.render = Some(RenderMode::SideBySide);
+        options.grid = GridStyle::Ascii;
+        let (_, text) = run(&options);
+
+        assert!(text.contains("max depth 3\nstart           1: Right        2: Down\n"));
+        assert!(text.ends_with("+---+---+---+   +---+---+---+   +---+---+---+\n"));
+    }
+
+    #[test]
+    fn test_unsolvable_board_is_reported() {
+        let (found, text) = run(&SolveOptions::new([2, 1, 3, 4, 5, 6, 7, 8, 0]));
+
+        assert!(!found);
+        assert!(text.starts_with("Unsolvable problem"));
+    }
+
+    #[test]
+    fn test_check_compares_parity_with_the_goal() {
+        let mut out = vec![];
+        let options = CheckOptions {
+            board: [2, 1, 3, 4, 5, 6, 7, 8, 0],
+            goal: [1, 2, 3, 4, 5, 6, 7, 8, 0],
+        };
+
+        assert_eq!(Ok(false), check(&options, &mut out));
+        assert_eq!(
+            Ok(true),
+            check(
+                &CheckOptions {
+                    goal: [1, 2, 3, 4, 5, 6, 8, 7, 0],
+                    ..options
+                },
+                &mut out
+            )
+        );
+    }
+
+    #[test]
+    fn test_closed_output_is_not_a_failure() {
+        let closed = std::io::Error::from(std::io::ErrorKind::BrokenPipe);
+        let full = std::io::Error::from(std::io::ErrorKind::WriteZero);
+
+        assert_eq!(crate::cli::OUTPUT_CLOSED, io_error(closed));
+        assert!(io_error(full).starts_with("failed to write output"));
+    }
+}
@@ -0,0 +1,179 @@
+//! `verify` subcommand
+//! Replays a move string on the board to check solutions coming from other solvers.
+
+use std::io::Write;
+
+use crate::cli::args::{GridStyle, VerifyOptions};
+use crate::cli::render::{frames, write_side_by_side};
+use crate::cli::solve::{format_board, io_error};
+use crate::eight_puzzle::{format_moves, EightPuzzleState, PuzzleStateRow, TileDirection};
+
+#[derive(Debug, Clone)]
+pub struct Verification {
+    /// the board followed by the state after every legal move
+    pub states: Vec<EightPuzzleState>,
+    /// index of the first move the blank can not make
+    pub illegal_move: Option<usize>,
+    pub reached_goal: bool,
+}
+
+/// applies the moves one by one, stopping at the first illegal move
+pub fn verify_moves(
+    board: PuzzleStateRow,
+    moves: &[TileDirection],
+    goal: PuzzleStateRow,
+) -> Verification {
+    let mut states = vec![EightPuzzleState::new(board)];
+    let mut illegal_move = None;
+
+    for (i, direction) in moves.iter().enumerate() {
+        let state = states.last().unwrap();
+        if !state.possible_actions().contains(direction) {
+            illegal_move = Some(i);
+            break;
+        }
+        let next = state.next_state(direction);
+        states.push(next);
+    }
+
+    let reached_goal = illegal_move.is_none() && states.last().unwrap().value() == &goal;
+    Verification {
+        states,
+        illegal_move,
+        reached_goal,
+    }
+}
+
+/// returns true if the moves lead from the board to the goal
+pub fn verify(options: &VerifyOptions, out: &mut dyn Write) -> Result<bool, String> {
+    let res = verify_moves(options.board, &options.moves, options.goal);
+
+    if options.show {
+        // the boards up to the first illegal move
+        let legal = &options.moves[..res.states.len() - 1];
+        write_side_by_side(&frames(options.board, legal), GridStyle::Ascii, out)
+            .map_err(io_error)?;
+    }
+
+    let moves = format_moves(&options.moves);
+    let last = res.states.last().unwrap();
+    match res.illegal_move {
+        Some(i) => {
+            let possible = format_moves(&last.possible_actions());
+            writeln!(
+                out,
+                "move {} ({}) is not possible from {}, the blank can move only {}",
+                i + 1,
+                options.moves[i].letter(),
+                format_board(last.value()),
+                possible
+            )
+        }
+        None if res.reached_goal => writeln!(
+            out,
+            "{:?} solves {} in {} moves",
+            moves,
+            format_board(&options.board),
+            options.moves.len()
+        ),
+        None => writeln!(
+            out,
+            "{:?} ends at {}, not at the goal {}",
+            moves,
+            format_board(last.value()),
+            format_board(&options.goal)
+        ),
+    }
+    .map_err(io_error)?;
+
+    Ok(res.reached_goal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::args::parse_board;
+    use crate::eight_puzzle::{parse_moves, DEFAULT_GOAL};
+
+    fn run(board: PuzzleStateRow, moves: &str, show: bool) -> (bool, String) {
+        let options = VerifyOptions {
+            board,
+            moves: parse_moves(moves).unwrap(),
+            goal: DEFAULT_GOAL,
+            show,
+        };
+        let mut out = vec![];
+        let solved = verify(&options, &mut out).unwrap();
+
+        (solved, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_moves_reaching_the_goal() {
+        let (solved, text) = run([1, 2, 3, 4, 0, 5, 7, 8, 6], "RD", true);
+
+        assert!(solved);
+        assert!(text.starts_with("start           1: Right        2: Down\n"));
+        assert!(text.contains("| 4 |   | 5 |   | 4 |[5]|   |   | 4 | 5 |[6]|\n"));
+        assert!(text.ends_with("\"RD\" solves 1,2,3,4,0,5,7,8,6 in 2 moves\n"));
+    }
+
+    #[test]
+    fn test_illegal_move_is_reported_with_its_position() {
+        let res = verify_moves(
+            [1, 2, 3, 4, 0, 5, 7, 8, 6],
+            &parse_moves("RRD").unwrap(),
+            DEFAULT_GOAL,
+        );
+
+        assert_eq!(Some(1), res.illegal_move);
+        assert_eq!(2, res.states.len());
+        assert!(!res.reached_goal);
+
+        let (solved, text) = run([1, 2, 3, 4, 0, 5, 7, 8, 6], "RRD", false);
+        assert!(!solved);
+        assert_eq!(
+            "move 2 (R) is not possible from 1,2,3,4,5,0,7,8,6, the blank can move only UDL\n",
+            text
+        );
+
+        // the boards end before the illegal move
+        let (_, text) = run([1, 2, 3, 4, 0, 5, 7, 8, 6], "RRD", true);
+        assert!(text.starts_with("start           1: Right\n"));
+    }
+
+    #[test]
+    fn test_moves_ending_elsewhere() {
+        let (solved, text) = run([1, 2, 3, 4, 0, 5, 7, 8, 6], "R", false);
+
+        assert!(!solved);
+        assert_eq!(
+            "\"R\" ends at 1,2,3,4,5,0,7,8,6, not at the goal 1,2,3,4,5,6,7,8,0\n",
+            text
+        );
+    }
+
+    #[test]
+    fn test_readme_solutions() {
+        let readme = include_str!("../../README.md");
+        let rows: Vec<Vec<&str>> = readme
+            .lines()
+            .skip_while(|line| !line.starts_with("## Examples"))
+            .filter(|line| line.starts_with("| ") && line.contains(','))
+            .map(|line| line.split('|').map(str::trim).collect())
+            .collect();
+
+        assert_eq!(15, rows.len());
+        for row in rows.iter() {
+            let board = parse_board(row[1]).unwrap();
+            let length: usize = row[2].parse().unwrap();
+            for solution in row[3].split(" or ") {
+                let moves = parse_moves(solution).unwrap();
+                let res = verify_moves(board, &moves, DEFAULT_GOAL);
+
+                assert!(res.reached_goal, "{} does not solve {}", solution, row[1]);
+                assert_eq!(length, moves.len(), "{} for {}", solution, row[1]);
+            }
+        }
+    }
+}
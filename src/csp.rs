@@ -0,0 +1,193 @@
+//! Constraint satisfaction problems
+//! Every variable takes a value from its domain so that all binary constraints hold.
+//! source:
+//! AIMA 3rd edition, chapter 6
+//! https://github.com/aimacode/aima-python/blob/master/csp.py
+
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+pub mod backtracking;
+pub mod consistency;
+pub mod local;
+pub mod map_coloring;
+pub mod sudoku;
+
+pub type Variable = usize;
+pub type Value = i32;
+/// value of every variable, None while it is not assigned yet
+pub type Assignment = Vec<Option<Value>>;
+pub type Domains = Vec<Vec<Value>>;
+
+type Constraint = Rc<dyn Fn(Value, Value) -> bool>;
+
+#[derive(Clone, Default)]
+pub struct Csp {
+    names: Vec<String>,
+    domains: Domains,
+    neighbors: Vec<Vec<Variable>>,
+    // constraint of every arc, stored for both directions
+    constraints: HashMap<(Variable, Variable), Constraint>,
+}
+
+impl Csp {
+    pub fn new() -> Self {
+        Csp::default()
+    }
+
+    pub fn add_variable(&mut self, name: &str, domain: Vec<Value>) -> Variable {
+        self.names.push(name.to_string());
+        self.domains.push(domain);
+        self.neighbors.push(vec![]);
+
+        self.names.len() - 1
+    }
+
+    /// adds constraint that `(value of x, value of y)` must satisfy;
+    /// a second constraint between the same variables must hold as well
+    pub fn add_constraint<F>(&mut self, x: Variable, y: Variable, constraint: F)
+    where
+        F: Fn(Value, Value) -> bool + 'static,
+    {
+        assert_ne!(x, y, "constraint must be binary");
+
+        let constraint: Constraint = match self.constraints.get(&(x, y)) {
+            Some(previous) => {
+                let previous = previous.clone();
+                Rc::new(move |a, b| previous(a, b) && constraint(a, b))
+            }
+            None => {
+                self.neighbors[x].push(y);
+                self.neighbors[y].push(x);
+                Rc::new(constraint)
+            }
+        };
+        let reversed = constraint.clone();
+
+        self.constraints.insert((x, y), constraint);
+        self.constraints
+            .insert((y, x), Rc::new(move |b, a| reversed(a, b)));
+    }
+
+    pub fn add_not_equal(&mut self, x: Variable, y: Variable) {
+        self.add_constraint(x, y, |a, b| a != b);
+    }
+
+    /// all pairs of the variables must have different values
+    pub fn add_all_different(&mut self, variables: &[Variable]) {
+        for (i, &x) in variables.iter().enumerate() {
+            for &y in variables.iter().skip(i + 1) {
+                self.add_not_equal(x, y);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn name(&self, variable: Variable) -> &str {
+        &self.names[variable]
+    }
+
+    pub fn variable(&self, name: &str) -> Option<Variable> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    pub fn domains(&self) -> &Domains {
+        &self.domains
+    }
+
+    pub fn neighbors(&self, variable: Variable) -> &[Variable] {
+        &self.neighbors[variable]
+    }
+
+    /// true if x = a and y = b do not break constraint between them
+    pub fn is_consistent(&self, x: Variable, a: Value, y: Variable, b: Value) -> bool {
+        match self.constraints.get(&(x, y)) {
+            Some(constraint) => constraint(a, b),
+            None => true,
+        }
+    }
+
+    /// number of assigned neighbors that conflict with the variable taking the value
+    pub fn conflicts(&self, variable: Variable, value: Value, assignment: &Assignment) -> usize {
+        self.neighbors[variable]
+            .iter()
+            .filter(|&&other| match assignment[other] {
+                Some(other_value) => !self.is_consistent(variable, value, other, other_value),
+                None => false,
+            })
+            .count()
+    }
+
+    /// true if every variable has a value from its domain and no constraint is broken
+    pub fn is_solution(&self, values: &[Value]) -> bool {
+        let assignment: Assignment = values.iter().map(|&v| Some(v)).collect();
+
+        values.len() == self.len()
+            && values.iter().enumerate().all(|(variable, value)| {
+                self.domains[variable].contains(value)
+                    && self.conflicts(variable, *value, &assignment) == 0
+            })
+    }
+}
+
+impl fmt::Debug for Csp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Csp")
+            .field("variables", &self.names)
+            .field("constraints", &(self.constraints.len() / 2))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constraints_work_in_both_directions() {
+        let mut csp = Csp::new();
+        let x = csp.add_variable("x", vec![1, 2, 3]);
+        let y = csp.add_variable("y", vec![1, 2, 3]);
+        csp.add_constraint(x, y, |a, b| a < b);
+
+        assert!(csp.is_consistent(x, 1, y, 2));
+        assert!(!csp.is_consistent(x, 2, y, 1));
+        assert!(csp.is_consistent(y, 2, x, 1));
+        assert!(!csp.is_consistent(y, 1, x, 2));
+    }
+
+    #[test]
+    fn test_constraints_between_same_variables_are_combined() {
+        let mut csp = Csp::new();
+        let x = csp.add_variable("x", vec![1, 2, 3]);
+        let y = csp.add_variable("y", vec![1, 2, 3]);
+        csp.add_constraint(x, y, |a, b| a < b);
+        csp.add_constraint(y, x, |b, a| b - a > 1);
+
+        assert_eq!(vec![y], csp.neighbors(x));
+        assert!(!csp.is_consistent(x, 1, y, 2));
+        assert!(csp.is_solution(&[1, 3]));
+    }
+
+    #[test]
+    fn test_conflicts_counts_only_assigned_neighbors() {
+        let mut csp = Csp::new();
+        let vars: Vec<Variable> = ["a", "b", "c"]
+            .iter()
+            .map(|name| csp.add_variable(name, vec![0, 1]))
+            .collect();
+        csp.add_all_different(&vars);
+
+        assert_eq!(1, csp.conflicts(0, 0, &vec![None, Some(0), None]));
+        assert_eq!(2, csp.conflicts(0, 0, &vec![None, Some(0), Some(0)]));
+        assert!(!csp.is_solution(&[0, 1, 0]));
+    }
+}
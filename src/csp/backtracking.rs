@@ -0,0 +1,301 @@
+//! Backtracking search for CSPs
+//! Assigns one variable at a time and goes back as soon as a constraint is broken;
+//! variable and value ordering and the inference after every assignment are configurable.
+//! source:
+//! AIMA 3rd edition, chapter 6.3, figure 6.5
+
+use std::collections::VecDeque;
+
+use crate::csp::consistency::propagate;
+use crate::csp::{Assignment, Csp, Domains, Value, Variable};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum VariableOrder {
+    /// the first unassigned variable
+    Static,
+    /// minimum remaining values - the variable with the fewest legal values
+    Mrv,
+    /// MRV, ties broken by the degree heuristic - the most constraints on unassigned variables
+    MrvDegree,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ValueOrder {
+    /// values in the order of the domain
+    Static,
+    /// least constraining value - rules out the fewest values of the neighbors first
+    LeastConstraining,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Inference {
+    None,
+    /// removes values inconsistent with the assignment from domains of the neighbors
+    ForwardChecking,
+    /// maintains arc consistency, runs AC-3 from the neighbors of the assigned variable
+    Mac,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BacktrackingConfig {
+    pub variable_order: VariableOrder,
+    pub value_order: ValueOrder,
+    pub inference: Inference,
+}
+
+impl Default for BacktrackingConfig {
+    fn default() -> Self {
+        BacktrackingConfig {
+            variable_order: VariableOrder::MrvDegree,
+            value_order: ValueOrder::LeastConstraining,
+            inference: Inference::ForwardChecking,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CspResult {
+    /// value of every variable, None if there is no solution
+    pub assignment: Option<Vec<Value>>,
+    /// number of values tried
+    pub assignments: usize,
+    /// number of times the search went back
+    pub backtracks: usize,
+}
+
+pub fn backtracking_search(csp: &Csp, config: &BacktrackingConfig) -> CspResult {
+    let mut search = Backtracking {
+        csp,
+        config,
+        assignments: 0,
+        backtracks: 0,
+    };
+    let mut assignment: Assignment = vec![None; csp.len()];
+    let found = search.backtrack(&mut assignment, csp.domains().clone());
+
+    CspResult {
+        assignment: if found {
+            assignment.into_iter().collect()
+        } else {
+            None
+        },
+        assignments: search.assignments,
+        backtracks: search.backtracks,
+    }
+}
+
+struct Backtracking<'a> {
+    csp: &'a Csp,
+    config: &'a BacktrackingConfig,
+    assignments: usize,
+    backtracks: usize,
+}
+
+impl Backtracking<'_> {
+    fn backtrack(&mut self, assignment: &mut Assignment, domains: Domains) -> bool {
+        let variable = match self.select_variable(assignment, &domains) {
+            Some(variable) => variable,
+            None => return true,
+        };
+
+        for value in self.order_values(variable, assignment, &domains) {
+            if self.csp.conflicts(variable, value, assignment) > 0 {
+                continue;
+            }
+
+            self.assignments += 1;
+            assignment[variable] = Some(value);
+
+            let mut next_domains = domains.clone();
+            next_domains[variable] = vec![value];
+            if self.infer(variable, value, assignment, &mut next_domains)
+                && self.backtrack(assignment, next_domains)
+            {
+                return true;
+            }
+
+            assignment[variable] = None;
+        }
+
+        self.backtracks += 1;
+        false
+    }
+
+    fn select_variable(&self, assignment: &Assignment, domains: &Domains) -> Option<Variable> {
+        let mut unassigned = (0..self.csp.len()).filter(|&v| assignment[v].is_none());
+
+        match self.config.variable_order {
+            VariableOrder::Static => unassigned.next(),
+            VariableOrder::Mrv => {
+                unassigned.min_by_key(|&v| self.legal_values(v, assignment, domains))
+            }
+            VariableOrder::MrvDegree => unassigned.min_by_key(|&v| {
+                let degree = self
+                    .csp
+                    .neighbors(v)
+                    .iter()
+                    .filter(|&&n| assignment[n].is_none())
+                    .count();
+                (
+                    self.legal_values(v, assignment, domains),
+                    usize::MAX - degree,
+                )
+            }),
+        }
+    }
+
+    fn legal_values(
+        &self,
+        variable: Variable,
+        assignment: &Assignment,
+        domains: &Domains,
+    ) -> usize {
+        domains[variable]
+            .iter()
+            .filter(|&&value| self.csp.conflicts(variable, value, assignment) == 0)
+            .count()
+    }
+
+    fn order_values(
+        &self,
+        variable: Variable,
+        assignment: &Assignment,
+        domains: &Domains,
+    ) -> Vec<Value> {
+        let mut values = domains[variable].clone();
+
+        if self.config.value_order == ValueOrder::LeastConstraining {
+            // stable sort keeps the domain order for ties
+            values.sort_by_key(|&value| {
+                self.csp
+                    .neighbors(variable)
+                    .iter()
+                    .filter(|&&n| assignment[n].is_none())
+                    .map(|&n| {
+                        domains[n]
+                            .iter()
+                            .filter(|&&b| !self.csp.is_consistent(variable, value, n, b))
+                            .count()
+                    })
+                    .sum::<usize>()
+            });
+        }
+
+        values
+    }
+
+    /// returns false if the assignment leaves some variable without values
+    fn infer(
+        &self,
+        variable: Variable,
+        value: Value,
+        assignment: &Assignment,
+        domains: &mut Domains,
+    ) -> bool {
+        let unassigned_neighbors = self
+            .csp
+            .neighbors(variable)
+            .iter()
+            .filter(|&&n| assignment[n].is_none());
+
+        match self.config.inference {
+            Inference::None => true,
+            Inference::ForwardChecking => {
+                for &n in unassigned_neighbors {
+                    domains[n].retain(|&b| self.csp.is_consistent(variable, value, n, b));
+                    if domains[n].is_empty() {
+                        return false;
+                    }
+                }
+                true
+            }
+            Inference::Mac => {
+                let arcs: VecDeque<(Variable, Variable)> =
+                    unassigned_neighbors.map(|&n| (n, variable)).collect();
+                propagate(self.csp, domains, arcs)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csp::map_coloring::australia;
+
+    fn all_configs() -> Vec<BacktrackingConfig> {
+        let mut configs = vec![];
+        for &variable_order in [
+            VariableOrder::Static,
+            VariableOrder::Mrv,
+            VariableOrder::MrvDegree,
+        ]
+        .iter()
+        {
+            for &value_order in [ValueOrder::Static, ValueOrder::LeastConstraining].iter() {
+                for &inference in
+                    [Inference::None, Inference::ForwardChecking, Inference::Mac].iter()
+                {
+                    configs.push(BacktrackingConfig {
+                        variable_order,
+                        value_order,
+                        inference,
+                    });
+                }
+            }
+        }
+
+        configs
+    }
+
+    #[test]
+    fn test_every_config_colors_australia() {
+        let csp = australia();
+
+        for config in all_configs().iter() {
+            let res = backtracking_search(&csp, config);
+            assert!(csp.is_solution(&res.assignment.unwrap()), "{:?}", config);
+        }
+    }
+
+    #[test]
+    fn test_unsolvable_problem_returns_none() {
+        let mut csp = Csp::new();
+        let vars: Vec<Variable> = ["a", "b", "c"]
+            .iter()
+            .map(|name| csp.add_variable(name, vec![0, 1]))
+            .collect();
+        csp.add_all_different(&vars);
+
+        for config in all_configs().iter() {
+            assert_eq!(None, backtracking_search(&csp, config).assignment);
+        }
+    }
+
+    #[test]
+    fn test_degree_heuristic_starts_with_most_constrained_region() {
+        let csp = australia();
+        let config = BacktrackingConfig {
+            variable_order: VariableOrder::MrvDegree,
+            value_order: ValueOrder::Static,
+            inference: Inference::None,
+        };
+        let search = Backtracking {
+            csp: &csp,
+            config: &config,
+            assignments: 0,
+            backtracks: 0,
+        };
+        let first = search.select_variable(&vec![None; csp.len()], csp.domains());
+
+        assert_eq!(csp.variable("SA"), first);
+    }
+
+    #[test]
+    fn test_mrv_with_forward_checking_colors_without_backtracking() {
+        let res = backtracking_search(&australia(), &BacktrackingConfig::default());
+
+        assert_eq!(0, res.backtracks);
+        assert_eq!(7, res.assignments);
+    }
+}
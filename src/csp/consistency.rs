@@ -0,0 +1,83 @@
+//! Arc consistency
+//! Removes values that have no support in a neighbor's domain, until nothing changes.
+//! source:
+//! AIMA 3rd edition, chapter 6.2.2, figure 6.3
+
+use std::collections::VecDeque;
+
+use crate::csp::{Csp, Domains, Variable};
+
+/// AC-3 over all arcs; returns false if some domain became empty
+pub fn ac3(csp: &Csp, domains: &mut Domains) -> bool {
+    let arcs = (0..csp.len())
+        .flat_map(|x| csp.neighbors(x).iter().map(move |&y| (x, y)))
+        .collect();
+
+    propagate(csp, domains, arcs)
+}
+
+/// AC-3 starting only from the given arcs, used to maintain arc consistency during search
+pub(crate) fn propagate(
+    csp: &Csp,
+    domains: &mut Domains,
+    mut queue: VecDeque<(Variable, Variable)>,
+) -> bool {
+    while let Some((x, y)) = queue.pop_front() {
+        if revise(csp, domains, x, y) {
+            if domains[x].is_empty() {
+                return false;
+            }
+            for &z in csp.neighbors(x).iter() {
+                if z != y {
+                    queue.push_back((z, x));
+                }
+            }
+        }
+    }
+
+    true
+}
+
+/// removes values of x without any consistent value of y; returns true if the domain changed
+fn revise(csp: &Csp, domains: &mut Domains, x: Variable, y: Variable) -> bool {
+    let before = domains[x].len();
+    let supports = domains[y].clone();
+
+    domains[x].retain(|&a| supports.iter().any(|&b| csp.is_consistent(x, a, y, b)));
+    domains[x].len() != before
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ac3_narrows_domains_of_a_chain() {
+        let mut csp = Csp::new();
+        let x = csp.add_variable("x", vec![1, 2, 3]);
+        let y = csp.add_variable("y", vec![1, 2, 3]);
+        let z = csp.add_variable("z", vec![1, 2, 3]);
+        csp.add_constraint(x, y, |a, b| a < b);
+        csp.add_constraint(y, z, |a, b| a < b);
+
+        let mut domains = csp.domains().clone();
+        assert!(ac3(&csp, &mut domains));
+        assert_eq!(vec![vec![1], vec![2], vec![3]], domains);
+    }
+
+    #[test]
+    fn test_ac3_detects_empty_domain() {
+        let mut csp = Csp::new();
+        let vars: Vec<Variable> = ["a", "b", "c"]
+            .iter()
+            .map(|name| csp.add_variable(name, vec![0, 1]))
+            .collect();
+        csp.add_all_different(&vars);
+
+        let mut domains = csp.domains().clone();
+        domains[0] = vec![0];
+        domains[1] = vec![1];
+
+        assert!(!ac3(&csp, &mut domains));
+    }
+}
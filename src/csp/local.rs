@@ -0,0 +1,133 @@
+//! Min-conflicts local search for CSPs
+//! Starts with a complete assignment and keeps changing a conflicted variable
+//! to the value with the fewest conflicts.
+//! source:
+//! AIMA 3rd edition, chapter 6.4, figure 6.8
+
+use crate::csp::{Assignment, Csp, Value};
+use crate::random::Random;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinConflictsResult {
+    pub assignment: Vec<Value>,
+    pub steps: usize,
+    /// number of broken constraints left, 0 for a solution
+    pub conflicts: usize,
+}
+
+impl MinConflictsResult {
+    pub fn is_solution(&self) -> bool {
+        self.conflicts == 0
+    }
+}
+
+pub fn min_conflicts(csp: &Csp, max_steps: usize, rng: &mut Random) -> MinConflictsResult {
+    // greedy start, every variable takes the value with fewest conflicts so far
+    let mut assignment: Assignment = vec![None; csp.len()];
+    for variable in 0..csp.len() {
+        assignment[variable] = Some(min_conflict_value(csp, variable, &assignment, rng));
+    }
+
+    let mut steps = 0;
+    while steps < max_steps {
+        let conflicted: Vec<usize> = (0..csp.len())
+            .filter(|&v| csp.conflicts(v, assignment[v].unwrap(), &assignment) > 0)
+            .collect();
+        let variable = match rng.choose(&conflicted) {
+            Some(&variable) => variable,
+            None => break,
+        };
+
+        steps += 1;
+        assignment[variable] = Some(min_conflict_value(csp, variable, &assignment, rng));
+    }
+
+    let values: Vec<Value> = assignment.into_iter().map(|v| v.unwrap()).collect();
+    MinConflictsResult {
+        conflicts: total_conflicts(csp, &values),
+        assignment: values,
+        steps,
+    }
+}
+
+// ties are broken randomly, so the search does not cycle between the same values
+fn min_conflict_value(
+    csp: &Csp,
+    variable: usize,
+    assignment: &Assignment,
+    rng: &mut Random,
+) -> Value {
+    let domain = &csp.domains()[variable];
+    let conflicts: Vec<usize> = domain
+        .iter()
+        .map(|&value| csp.conflicts(variable, value, assignment))
+        .collect();
+    let fewest = *conflicts.iter().min().expect("domain must not be empty");
+    let best: Vec<Value> = domain
+        .iter()
+        .zip(conflicts.iter())
+        .filter(|(_, &c)| c == fewest)
+        .map(|(&value, _)| value)
+        .collect();
+
+    *rng.choose(&best).unwrap()
+}
+
+fn total_conflicts(csp: &Csp, values: &[Value]) -> usize {
+    let assignment: Assignment = values.iter().map(|&v| Some(v)).collect();
+
+    // every broken constraint is counted from both of its variables
+    (0..csp.len())
+        .map(|v| csp.conflicts(v, values[v], &assignment))
+        .sum::<usize>()
+        / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csp::map_coloring::australia;
+    use crate::csp::Variable;
+
+    // n queens, one variable per column with the row of the queen
+    fn queens(n: usize) -> Csp {
+        let mut csp = Csp::new();
+        let vars: Vec<Variable> = (0..n)
+            .map(|i| csp.add_variable(&format!("Q{}", i + 1), (0..n as Value).collect()))
+            .collect();
+        for (i, &x) in vars.iter().enumerate() {
+            for (j, &y) in vars.iter().enumerate().skip(i + 1) {
+                let distance = (j - i) as Value;
+                csp.add_constraint(x, y, move |a, b| a != b && (a - b).abs() != distance);
+            }
+        }
+
+        csp
+    }
+
+    #[test]
+    fn test_min_conflicts_colors_australia() {
+        let csp = australia();
+        let res = min_conflicts(&csp, 1000, &mut Random::new(1));
+
+        assert!(res.is_solution());
+        assert!(csp.is_solution(&res.assignment));
+    }
+
+    #[test]
+    fn test_min_conflicts_places_many_queens() {
+        let csp = queens(50);
+        let res = min_conflicts(&csp, 10_000, &mut Random::new(7));
+
+        assert!(res.is_solution());
+        assert!(csp.is_solution(&res.assignment));
+    }
+
+    #[test]
+    fn test_min_conflicts_reports_conflicts_when_out_of_steps() {
+        let res = min_conflicts(&queens(3), 100, &mut Random::new(3));
+
+        assert!(!res.is_solution());
+        assert_eq!(100, res.steps);
+    }
+}
@@ -0,0 +1,103 @@
+//! Map coloring
+//! Neighboring regions must have different colors.
+//! source:
+//! AIMA 3rd edition, chapter 6.1.1, figure 6.1
+
+use crate::csp::{Csp, Value};
+
+pub const COLORS: [&str; 4] = ["red", "green", "blue", "yellow"];
+
+/// every region takes one of the first `colors` colors
+pub fn map_coloring(
+    regions: &[&str],
+    borders: &[(&str, &str)],
+    colors: usize,
+) -> Result<Csp, String> {
+    if colors == 0 || colors > COLORS.len() {
+        return Err(format!(
+            "expected 1 to {} colors but got {}",
+            COLORS.len(),
+            colors
+        ));
+    }
+
+    let mut csp = Csp::new();
+    for region in regions.iter() {
+        csp.add_variable(region, (0..colors as Value).collect());
+    }
+    for (first, second) in borders.iter() {
+        let x = csp
+            .variable(first)
+            .ok_or_else(|| format!("unknown region {}", first))?;
+        let y = csp
+            .variable(second)
+            .ok_or_else(|| format!("unknown region {}", second))?;
+        csp.add_not_equal(x, y);
+    }
+
+    Ok(csp)
+}
+
+/// states and territories of Australia with three colors; Tasmania has no neighbors
+pub fn australia() -> Csp {
+    let regions = ["WA", "NT", "SA", "Q", "NSW", "V", "T"];
+    let borders = [
+        ("WA", "NT"),
+        ("WA", "SA"),
+        ("NT", "SA"),
+        ("NT", "Q"),
+        ("SA", "Q"),
+        ("SA", "NSW"),
+        ("SA", "V"),
+        ("Q", "NSW"),
+        ("NSW", "V"),
+    ];
+
+    map_coloring(&regions, &borders, 3).expect("map of Australia is valid")
+}
+
+pub fn color_name(value: Value) -> &'static str {
+    COLORS[value as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csp::backtracking::{backtracking_search, BacktrackingConfig};
+
+    #[test]
+    fn test_australia_has_seven_regions() {
+        let csp = australia();
+
+        assert_eq!(7, csp.len());
+        assert_eq!(5, csp.neighbors(csp.variable("SA").unwrap()).len());
+        assert!(csp.neighbors(csp.variable("T").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_triangle_needs_three_colors() {
+        let regions = ["A", "B", "C"];
+        let borders = [("A", "B"), ("B", "C"), ("C", "A")];
+        let two = map_coloring(&regions, &borders, 2).unwrap();
+        let three = map_coloring(&regions, &borders, 3).unwrap();
+
+        let config = BacktrackingConfig::default();
+        assert_eq!(None, backtracking_search(&two, &config).assignment);
+        assert_eq!(
+            vec!["red", "green", "blue"],
+            backtracking_search(&three, &config)
+                .assignment
+                .unwrap()
+                .into_iter()
+                .map(color_name)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_map_coloring_rejects_unknown_region() {
+        let err = map_coloring(&["A"], &[("A", "B")], 2).unwrap_err();
+
+        assert_eq!("unknown region B", err);
+    }
+}
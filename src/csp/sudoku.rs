@@ -0,0 +1,129 @@
+//! Sudoku as a CSP
+//! 81 variables with digits 1-9; every row, column and 3x3 box has all different digits.
+//! Puzzles are read as 81 characters row by row, `.` or `0` for an empty cell.
+//! source:
+//! AIMA 3rd edition, chapter 6.2.6
+
+use crate::csp::{Csp, Value, Variable};
+
+pub const CELLS: usize = 81;
+const SIZE: usize = 9;
+
+pub fn sudoku(puzzle: &str) -> Result<Csp, String> {
+    let cells: Vec<char> = puzzle.chars().filter(|c| !c.is_whitespace()).collect();
+    if cells.len() != CELLS {
+        return Err(format!("expected {} cells but got {}", CELLS, cells.len()));
+    }
+
+    let mut csp = Csp::new();
+    for (i, &c) in cells.iter().enumerate() {
+        let domain = match c {
+            '.' | '0' => (1..=SIZE as Value).collect(),
+            '1'..='9' => vec![c.to_digit(10).unwrap() as Value],
+            _ => return Err(format!("unknown cell {:?}", c)),
+        };
+        csp.add_variable(&cell_name(i), domain);
+    }
+
+    for unit in units().iter() {
+        csp.add_all_different(unit);
+    }
+
+    Ok(csp)
+}
+
+/// solved grid as 9 lines of digits
+pub fn format_grid(values: &[Value]) -> String {
+    values
+        .chunks(SIZE)
+        .map(|row| row.iter().map(|v| v.to_string()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// cells are named by row letter and column number, A1 to I9
+fn cell_name(cell: usize) -> String {
+    format!(
+        "{}{}",
+        (b'A' + (cell / SIZE) as u8) as char,
+        cell % SIZE + 1
+    )
+}
+
+// rows, columns and boxes
+fn units() -> Vec<Vec<Variable>> {
+    let mut units = vec![];
+
+    for i in 0..SIZE {
+        units.push((0..SIZE).map(|j| i * SIZE + j).collect());
+        units.push((0..SIZE).map(|j| j * SIZE + i).collect());
+        let (top, left) = (i / 3 * 3, i % 3 * 3);
+        units.push(
+            (0..SIZE)
+                .map(|j| (top + j / 3) * SIZE + left + j % 3)
+                .collect(),
+        );
+    }
+
+    units
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csp::backtracking::{
+        backtracking_search, BacktrackingConfig, Inference, ValueOrder, VariableOrder,
+    };
+    use crate::csp::consistency::ac3;
+
+    // easy puzzle from aima-python, solved by AC-3 alone
+    const EASY: &str =
+        "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..";
+    // hard puzzle from aima-python
+    const HARD: &str =
+        "4173698.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+    #[test]
+    fn test_sudoku_cells_have_twenty_neighbors() {
+        let csp = sudoku(EASY).unwrap();
+
+        assert_eq!(CELLS, csp.len());
+        assert!((0..CELLS).all(|cell| csp.neighbors(cell).len() == 20));
+        assert_eq!("A3", csp.name(2));
+    }
+
+    #[test]
+    fn test_ac3_solves_easy_sudoku() {
+        let csp = sudoku(EASY).unwrap();
+        let mut domains = csp.domains().clone();
+
+        assert!(ac3(&csp, &mut domains));
+        assert!(domains.iter().all(|domain| domain.len() == 1));
+
+        let values: Vec<Value> = domains.iter().map(|domain| domain[0]).collect();
+        assert!(csp.is_solution(&values));
+        assert!(format_grid(&values).starts_with("483921657\n"));
+    }
+
+    #[test]
+    fn test_backtracking_solves_hard_sudoku() {
+        let csp = sudoku(HARD).unwrap();
+        let config = BacktrackingConfig {
+            variable_order: VariableOrder::Mrv,
+            value_order: ValueOrder::Static,
+            inference: Inference::Mac,
+        };
+        let res = backtracking_search(&csp, &config);
+
+        assert!(csp.is_solution(&res.assignment.unwrap()));
+    }
+
+    #[test]
+    fn test_sudoku_rejects_bad_input() {
+        assert_eq!("expected 81 cells but got 3", sudoku("123").unwrap_err());
+        assert_eq!(
+            "unknown cell 'x'",
+            sudoku(&EASY.replacen('.', "x", 1)).unwrap_err()
+        );
+    }
+}
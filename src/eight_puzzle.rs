@@ -30,11 +30,65 @@ impl TileDirection {
             TileDirection::None => 0,
         }
     }
+
+    /// direction that moves the blank back
+    pub fn opposite(&self) -> TileDirection {
+        match self {
+            TileDirection::Up => TileDirection::Down,
+            TileDirection::Down => TileDirection::Up,
+            TileDirection::Left => TileDirection::Right,
+            TileDirection::Right => TileDirection::Left,
+            TileDirection::None => TileDirection::None,
+        }
+    }
+
+    /// letter of the compact notation, e.g. `URDL`
+    pub fn letter(&self) -> char {
+        match self {
+            TileDirection::Up => 'U',
+            TileDirection::Down => 'D',
+            TileDirection::Left => 'L',
+            TileDirection::Right => 'R',
+            TileDirection::None => '-',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<TileDirection> {
+        match letter.to_ascii_uppercase() {
+            'U' => Some(TileDirection::Up),
+            'D' => Some(TileDirection::Down),
+            'L' => Some(TileDirection::Left),
+            'R' => Some(TileDirection::Right),
+            _ => None,
+        }
+    }
+}
+
+/// writes moves of the blank in the compact notation used by the README, e.g. `RD`
+pub fn format_moves(moves: &[TileDirection]) -> String {
+    moves
+        .iter()
+        .filter(|direction| **direction != TileDirection::None)
+        .map(TileDirection::letter)
+        .collect()
+}
+
+/// reads moves in the compact notation; letters are case insensitive,
+/// spaces and commas between them are skipped
+pub fn parse_moves(text: &str) -> Result<Vec<TileDirection>, String> {
+    text.chars()
+        .enumerate()
+        .filter(|(_, letter)| !letter.is_whitespace() && *letter != ',')
+        .map(|(i, letter)| {
+            TileDirection::from_letter(letter)
+                .ok_or_else(|| format!("unknown move {:?} at position {}", letter, i + 1))
+        })
+        .collect()
 }
 
-impl Into<Action> for TileDirection {
-    fn into(self) -> Action {
-        Action(self as usize)
+impl From<TileDirection> for Action {
+    fn from(direction: TileDirection) -> Self {
+        Action(direction as usize)
     }
 }
 
@@ -97,7 +151,7 @@ impl Tile {
     }
 
     pub fn is_valid_index(index: usize) {
-        assert!(index >= MIN_STATE_INDEX && index <= MAX_STATE_INDEX);
+        assert!((MIN_STATE_INDEX..=MAX_STATE_INDEX).contains(&index));
     }
 
     fn is_allowed_action(&self, action: &TileDirection) {
@@ -117,7 +171,7 @@ impl Tile {
     }
 
     fn can_go_left(&self) -> bool {
-        self.index % 3 != 0
+        !self.index.is_multiple_of(3)
     }
 }
 
@@ -146,7 +200,7 @@ impl EightPuzzleState {
         assert!(blank_square.check_action(action));
 
         let neighbor = blank_square.neighbor(action);
-        let mut new_value = self.value.clone();
+        let mut new_value = self.value;
 
         new_value.swap(blank_square.index, neighbor.index);
 
@@ -176,14 +230,29 @@ impl EightPuzzleState {
         inversion % 2 == 0
     }
 
+    /// true if the goal can be reached by sliding tiles, both boards need the same parity
+    pub fn can_reach(&self, goal: &EightPuzzleState) -> bool {
+        self.is_solveable() == goal.is_solveable()
+    }
+
     fn find_blank_square(&self) -> usize {
         self.value().iter().position(|&x| x == 0).unwrap()
     }
 }
 
+/// Heuristics estimating number of moves from a state to the goal
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Heuristic {
+    /// number of tiles not on their goal square, the blank is not counted (AIMA h1)
+    MisplacedTiles,
+    /// sum of horizontal and vertical distances of tiles from their goal squares
+    ManhattanDistance,
+}
+
 pub struct EightPuzzle {
     state: EightPuzzleState,
     goal: EightPuzzleState,
+    heuristic: Heuristic,
 }
 
 impl EightPuzzle {
@@ -191,39 +260,81 @@ impl EightPuzzle {
         EightPuzzle {
             state: initial_state,
             goal: EightPuzzleState::new(DEFAULT_GOAL),
+            heuristic: Heuristic::MisplacedTiles,
         }
     }
 
     pub fn from_row(state_row: PuzzleStateRow) -> Self {
-        EightPuzzle {
-            state: EightPuzzleState::new(state_row),
-            goal: EightPuzzleState::new(DEFAULT_GOAL),
-        }
+        EightPuzzle::new(EightPuzzleState::new(state_row))
+    }
+
+    pub fn with_heuristic(mut self, heuristic: Heuristic) -> Self {
+        self.heuristic = heuristic;
+        self
+    }
+
+    pub fn with_goal(mut self, goal: PuzzleStateRow) -> Self {
+        self.goal = EightPuzzleState::new(goal);
+        self
     }
 
     pub fn state(&self) -> &EightPuzzleState {
         &self.state
     }
 
+    pub fn heuristic(&self) -> Heuristic {
+        self.heuristic
+    }
+
+    pub fn goal(&self) -> &EightPuzzleState {
+        &self.goal
+    }
+
     // returns a problem from goal
     pub fn reverse_problem(&self) -> Self {
         EightPuzzle {
-            state: EightPuzzleState::new(DEFAULT_GOAL),
+            state: self.goal.clone(),
             goal: self.state.clone(),
+            heuristic: self.heuristic,
         }
     }
 
     // returns the heuristic value for a given state.
+    pub fn h(&self) -> u8 {
+        match self.heuristic {
+            Heuristic::MisplacedTiles => self.misplaced_tiles(),
+            Heuristic::ManhattanDistance => self.manhattan_distance(),
+        }
+    }
+
     // here it is the number of misplaces tiles
-    fn h(&self) -> u8 {
-        // TODO:
+    fn misplaced_tiles(&self) -> u8 {
         self.state()
             .value()
             .iter()
             .zip(self.goal.value().iter())
+            .filter(|(s, _)| **s != 0)
             .map(|(s, g)| if s != g { 1 } else { 0 })
             .sum()
     }
+
+    // blank square is not a tile, so it is not counted
+    fn manhattan_distance(&self) -> u8 {
+        let goal = self.goal.value();
+
+        self.state()
+            .value()
+            .iter()
+            .enumerate()
+            .filter(|(_, &tile)| tile != 0)
+            .map(|(i, tile)| {
+                let j = goal.iter().position(|g| g == tile).unwrap();
+                let rows = (i / 3).abs_diff(j / 3);
+                let cols = (i % 3).abs_diff(j % 3);
+                (rows + cols) as u8
+            })
+            .sum()
+    }
 }
 
 impl SearchProblem for EightPuzzle {
@@ -237,7 +348,11 @@ impl SearchProblem for EightPuzzle {
 
     fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
         let direction = TileDirection::from(action);
-        let solution = EightPuzzle::new(self.state.next_state(&direction));
+        let solution = EightPuzzle {
+            state: self.state.next_state(&direction),
+            goal: self.goal.clone(),
+            heuristic: self.heuristic,
+        };
         Box::new(solution)
     }
 
@@ -250,8 +365,9 @@ impl SearchProblem for EightPuzzle {
         1
     }
 
+    // heuristic estimate of the remaining cost, goal has value 0
     fn value(&self) -> u32 {
-        0
+        self.h() as u32
     }
 
     fn as_string(&self) -> String {
@@ -264,6 +380,10 @@ impl SearchProblem for EightPuzzle {
         self.hash(&mut hasher);
         hasher.finish()
     }
+
+    fn action_name(&self, action: &Action) -> String {
+        format!("{:?}", TileDirection::from(action))
+    }
 }
 
 impl Hash for EightPuzzle {
@@ -448,7 +568,8 @@ mod tests {
         let action: Action = TileDirection::Up.into();
 
         let res = puzzle.result(&action);
-        assert_eq!(0, res.value());
+        assert_eq!(1, res.value());
+        assert!(!res.test_goal());
     }
 
     #[test]
@@ -506,6 +627,77 @@ mod tests {
     fn test_eight_puzzle_h_with_2squares_swapped() {
         let puzzle = EightPuzzle::from_row([1, 2, 3, 4, 5, 6, 7, 0, 8]);
 
+        // only tile 8 is misplaced, the blank does not count
+        assert_eq!(1, puzzle.h());
+    }
+
+    #[test]
+    fn test_eight_puzzle_manhattan_distance_with_final_goal() {
+        let puzzle =
+            EightPuzzle::from_row(DEFAULT_GOAL).with_heuristic(Heuristic::ManhattanDistance);
+
+        assert_eq!(0, puzzle.h());
+    }
+
+    #[test]
+    fn test_eight_puzzle_manhattan_distance_skips_blank() {
+        let puzzle = EightPuzzle::from_row([1, 2, 3, 4, 5, 6, 0, 7, 8])
+            .with_heuristic(Heuristic::ManhattanDistance);
+
         assert_eq!(2, puzzle.h());
     }
+
+    #[test]
+    fn test_eight_puzzle_manhattan_distance_with_far_tiles() {
+        let puzzle = EightPuzzle::from_row([8, 7, 6, 5, 4, 3, 2, 1, 0])
+            .with_heuristic(Heuristic::ManhattanDistance);
+
+        assert_eq!(16, puzzle.h());
+    }
+
+    #[test]
+    fn test_eight_puzzle_result_keeps_goal_and_heuristic() {
+        let puzzle = EightPuzzle::from_row([1, 2, 3, 4, 5, 6, 7, 0, 8])
+            .with_heuristic(Heuristic::ManhattanDistance)
+            .reverse_problem();
+
+        assert_eq!(1, puzzle.value());
+        let res = puzzle.result(&TileDirection::Left.into());
+        assert_eq!(0, res.value());
+        assert!(res.test_goal());
+    }
+
+    #[test]
+    fn test_eight_puzzle_with_custom_goal() {
+        let goal = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+        let puzzle = EightPuzzle::from_row([1, 0, 2, 3, 4, 5, 6, 7, 8]).with_goal(goal);
+
+        assert!(!puzzle.test_goal());
+        assert!(puzzle.result(&TileDirection::Left.into()).test_goal());
+        assert_eq!(&goal, puzzle.reverse_problem().state().value());
+        assert!(puzzle.state().can_reach(puzzle.goal()));
+        assert!(!EightPuzzleState::new([2, 1, 0, 3, 4, 5, 6, 7, 8]).can_reach(puzzle.goal()));
+    }
+
+    #[test]
+    fn test_compact_moves_round_trip() {
+        let moves = vec![
+            TileDirection::Up,
+            TileDirection::Right,
+            TileDirection::Down,
+            TileDirection::Left,
+        ];
+
+        assert_eq!("URDL", format_moves(&moves));
+        assert_eq!(Ok(moves), parse_moves("urdl"));
+        assert_eq!(
+            Ok(vec![TileDirection::Right, TileDirection::Down]),
+            parse_moves("R, D")
+        );
+        assert_eq!("", format_moves(&[TileDirection::None]));
+        assert_eq!(
+            Err("unknown move 'X' at position 3".to_string()),
+            parse_moves("UDX")
+        );
+    }
 }
@@ -0,0 +1,51 @@
+//! Two player games with perfect information
+//! A game is played by players 0 and 1 taking turns; like `SearchProblem` every state
+//! is an object that creates the next states.
+//! source:
+//! AIMA 3rd edition, chapter 5
+//! https://github.com/aimacode/aima-python/blob/master/games.py
+
+use std::fmt;
+
+use crate::search::Action;
+
+pub mod adversarial;
+pub mod connect_four;
+pub mod mcts;
+pub mod tic_tac_toe;
+
+pub type Player = usize;
+
+pub trait Game {
+    fn to_move(&self) -> Player;
+    fn actions(&self) -> Vec<Action>;
+    fn result(&self, action: &Action) -> Box<dyn Game>;
+    fn is_terminal(&self) -> bool;
+    // final score of the player, positive for a win
+    fn utility(&self, player: Player) -> i32;
+    // estimate of the score used when the search is cut off before the end of the game
+    fn evaluate(&self, player: Player) -> i32 {
+        if self.is_terminal() {
+            self.utility(player)
+        } else {
+            0
+        }
+    }
+    fn as_string(&self) -> String;
+    // human readable name of an action
+    fn action_name(&self, action: &Action) -> String {
+        action.0.to_string()
+    }
+}
+
+impl fmt::Debug for dyn Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Game")
+            .field("state", &self.as_string())
+            .finish()
+    }
+}
+
+pub fn opponent(player: Player) -> Player {
+    1 - player
+}
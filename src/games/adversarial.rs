@@ -0,0 +1,257 @@
+//! Adversarial search
+//! Values are scored from the point of view of the player to move at the root:
+//! that player maximizes the value and the opponent minimizes it.
+//! source:
+//! AIMA 3rd edition, chapter 5.2 and 5.3, figures 5.3 and 5.7
+
+use crate::games::{Game, Player};
+use crate::search::Action;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameSearchResult {
+    /// best action for the player to move, None if the game is over
+    pub action: Option<Action>,
+    pub value: i32,
+    /// number of game states visited
+    pub nodes: usize,
+}
+
+/// Minimax explores the whole game tree
+pub fn minimax_decision(state: &dyn Game) -> GameSearchResult {
+    let player = state.to_move();
+    let mut nodes = 1;
+    let mut best: Option<(Action, i32)> = None;
+
+    for action in state.actions() {
+        let value = min_value(&*state.result(&action), player, &mut nodes);
+        if best.is_none_or(|(_, best_value)| value > best_value) {
+            best = Some((action, value));
+        }
+    }
+
+    decision(state, player, best, nodes)
+}
+
+fn max_value(state: &dyn Game, player: Player, nodes: &mut usize) -> i32 {
+    *nodes += 1;
+    if state.is_terminal() {
+        return state.utility(player);
+    }
+
+    state
+        .actions()
+        .iter()
+        .map(|action| min_value(&*state.result(action), player, nodes))
+        .max()
+        .unwrap()
+}
+
+fn min_value(state: &dyn Game, player: Player, nodes: &mut usize) -> i32 {
+    *nodes += 1;
+    if state.is_terminal() {
+        return state.utility(player);
+    }
+
+    state
+        .actions()
+        .iter()
+        .map(|action| max_value(&*state.result(action), player, nodes))
+        .min()
+        .unwrap()
+}
+
+/// Alpha-beta search returns the same value as minimax,
+/// but skips the branches that can not change the decision
+pub fn alpha_beta_search(state: &dyn Game) -> GameSearchResult {
+    AlphaBeta {
+        player: state.to_move(),
+        depth: None,
+        order_moves: false,
+        nodes: 0,
+    }
+    .decide(state)
+}
+
+/// Alpha-beta search that stops `depth` moves deep and scores the states there
+/// with `Game::evaluate`; with `order_moves` the most promising moves are tried first,
+/// which makes the pruning more effective
+pub fn alpha_beta_cutoff_search(
+    state: &dyn Game,
+    depth: usize,
+    order_moves: bool,
+) -> GameSearchResult {
+    AlphaBeta {
+        player: state.to_move(),
+        depth: Some(depth),
+        order_moves,
+        nodes: 0,
+    }
+    .decide(state)
+}
+
+struct AlphaBeta {
+    player: Player,
+    depth: Option<usize>,
+    order_moves: bool,
+    nodes: usize,
+}
+
+impl AlphaBeta {
+    fn decide(mut self, state: &dyn Game) -> GameSearchResult {
+        self.nodes += 1;
+
+        let mut alpha = i32::MIN;
+        let mut best: Option<(Action, i32)> = None;
+
+        for (action, child) in self.children(state, true) {
+            let value = self.value(&*child, alpha, i32::MAX, 1);
+            if best.is_none_or(|(_, best_value)| value > best_value) {
+                best = Some((action, value));
+            }
+            alpha = alpha.max(value);
+        }
+
+        decision(state, self.player, best, self.nodes)
+    }
+
+    fn value(&mut self, state: &dyn Game, mut alpha: i32, mut beta: i32, depth: usize) -> i32 {
+        self.nodes += 1;
+        if state.is_terminal() {
+            return state.utility(self.player);
+        }
+        if self.depth.is_some_and(|limit| depth >= limit) {
+            return state.evaluate(self.player);
+        }
+
+        let maximizing = state.to_move() == self.player;
+        let mut best = if maximizing { i32::MIN } else { i32::MAX };
+
+        for (_, child) in self.children(state, maximizing) {
+            let value = self.value(&*child, alpha, beta, depth + 1);
+            if maximizing {
+                best = best.max(value);
+                if best >= beta {
+                    return best;
+                }
+                alpha = alpha.max(best);
+            } else {
+                best = best.min(value);
+                if best <= alpha {
+                    return best;
+                }
+                beta = beta.min(best);
+            }
+        }
+
+        best
+    }
+
+    fn children(&self, state: &dyn Game, maximizing: bool) -> Vec<(Action, Box<dyn Game>)> {
+        let mut children: Vec<(Action, Box<dyn Game>)> = state
+            .actions()
+            .into_iter()
+            .map(|action| {
+                let child = state.result(&action);
+                (action, child)
+            })
+            .collect();
+
+        if self.order_moves {
+            // best moves for the player to move first; stable sort keeps ties in order
+            children.sort_by_key(|(_, child)| {
+                let score = child.evaluate(self.player);
+                if maximizing {
+                    -(score as i64)
+                } else {
+                    score as i64
+                }
+            });
+        }
+
+        children
+    }
+}
+
+fn decision(
+    state: &dyn Game,
+    player: Player,
+    best: Option<(Action, i32)>,
+    nodes: usize,
+) -> GameSearchResult {
+    match best {
+        Some((action, value)) => GameSearchResult {
+            action: Some(action),
+            value,
+            nodes,
+        },
+        None => GameSearchResult {
+            action: None,
+            value: state.evaluate(player),
+            nodes,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::connect_four::ConnectFour;
+    use crate::games::tic_tac_toe::TicTacToe;
+
+    #[test]
+    fn test_alpha_beta_agrees_with_minimax_and_visits_fewer_nodes() {
+        let game = TicTacToe::parse("X........").unwrap();
+        let minimax = minimax_decision(&game);
+        let alpha_beta = alpha_beta_search(&game);
+
+        assert_eq!(0, minimax.value);
+        assert_eq!(minimax.value, alpha_beta.value);
+        assert_eq!(59_705, minimax.nodes);
+        assert!(alpha_beta.nodes * 5 < minimax.nodes);
+    }
+
+    #[test]
+    fn test_minimax_takes_the_winning_move() {
+        // X to move wins with the top right corner
+        let game = TicTacToe::parse("XX.OO....").unwrap();
+        let res = minimax_decision(&game);
+
+        assert_eq!(Some(Action(2)), res.action);
+        assert_eq!(1, res.value);
+    }
+
+    #[test]
+    fn test_alpha_beta_blocks_the_opponent() {
+        // O to move has to block X in the middle column
+        let game = TicTacToe::parse(".X..X...O").unwrap();
+        let res = alpha_beta_search(&game);
+
+        assert_eq!(Some(Action(7)), res.action);
+    }
+
+    #[test]
+    fn test_search_on_finished_game_returns_no_action() {
+        let game = TicTacToe::parse("XXXOO....").unwrap();
+        let res = alpha_beta_search(&game);
+
+        assert_eq!(None, res.action);
+        assert_eq!(-1, res.value);
+    }
+
+    #[test]
+    fn test_cutoff_search_finds_connect_four_win() {
+        let game = ConnectFour::from_moves(&[3, 0, 3, 0, 3, 1]).unwrap();
+        let res = alpha_beta_cutoff_search(&game, 4, true);
+
+        assert_eq!(Some(Action(3)), res.action);
+    }
+
+    #[test]
+    fn test_move_ordering_visits_fewer_nodes() {
+        let game = ConnectFour::from_moves(&[3, 3, 2, 4]).unwrap();
+        let plain = alpha_beta_cutoff_search(&game, 5, false);
+        let ordered = alpha_beta_cutoff_search(&game, 5, true);
+
+        assert!(ordered.nodes < plain.nodes);
+    }
+}
@@ -0,0 +1,213 @@
+//! Connect four
+//! Players drop discs into 7 columns of 6 rows, four discs in a row win;
+//! the evaluation function scores every window of four cells that only one player uses.
+
+use crate::games::{opponent, Game, Player};
+use crate::search::Action;
+
+pub const COLUMNS: usize = 7;
+pub const ROWS: usize = 6;
+/// utility of a won game, far above any evaluation of an unfinished one
+pub const WIN: i32 = 1_000_000;
+
+const MARKS: [char; 2] = ['X', 'O'];
+// score of a window with 1, 2 or 3 discs of a single player
+const WINDOW_SCORES: [i32; 4] = [0, 1, 10, 100];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectFour {
+    // bottom row first
+    board: [[Option<Player>; COLUMNS]; ROWS],
+    to_move: Player,
+    winner: Option<Player>,
+}
+
+impl Default for ConnectFour {
+    fn default() -> Self {
+        ConnectFour::new()
+    }
+}
+
+impl ConnectFour {
+    pub fn new() -> Self {
+        ConnectFour {
+            board: [[None; COLUMNS]; ROWS],
+            to_move: 0,
+            winner: None,
+        }
+    }
+
+    /// plays the columns in order, starting with player 0
+    pub fn from_moves(columns: &[usize]) -> Result<Self, String> {
+        let mut game = ConnectFour::new();
+        for &column in columns.iter() {
+            if game.is_terminal() || column >= COLUMNS || game.height(column) == ROWS {
+                return Err(format!("column {} can not be played", column));
+            }
+            game = game.drop_disc(column);
+        }
+
+        Ok(game)
+    }
+
+    fn height(&self, column: usize) -> usize {
+        (0..ROWS)
+            .find(|&row| self.board[row][column].is_none())
+            .unwrap_or(ROWS)
+    }
+
+    fn drop_disc(&self, column: usize) -> Self {
+        let mut next = self.clone();
+        let row = self.height(column);
+
+        next.board[row][column] = Some(self.to_move);
+        next.to_move = opponent(self.to_move);
+        if next.windows().iter().any(|window| {
+            window.contains(&(row, column))
+                && window
+                    .iter()
+                    .all(|&(r, c)| next.board[r][c] == Some(self.to_move))
+        }) {
+            next.winner = Some(self.to_move);
+        }
+
+        next
+    }
+
+    // every four cells in a row, column or diagonal
+    fn windows(&self) -> Vec<[(usize, usize); 4]> {
+        let directions: [(i32, i32); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+        let mut windows = vec![];
+
+        for row in 0..ROWS as i32 {
+            for column in 0..COLUMNS as i32 {
+                for &(dr, dc) in directions.iter() {
+                    let (end_row, end_column) = (row + 3 * dr, column + 3 * dc);
+                    if (0..ROWS as i32).contains(&end_row)
+                        && (0..COLUMNS as i32).contains(&end_column)
+                    {
+                        let cell = |i: i32| ((row + i * dr) as usize, (column + i * dc) as usize);
+                        windows.push([cell(0), cell(1), cell(2), cell(3)]);
+                    }
+                }
+            }
+        }
+
+        windows
+    }
+}
+
+impl Game for ConnectFour {
+    fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        if self.winner.is_some() {
+            return vec![];
+        }
+
+        (0..COLUMNS)
+            .filter(|&column| self.height(column) < ROWS)
+            .map(Action)
+            .collect()
+    }
+
+    fn result(&self, action: &Action) -> Box<dyn Game> {
+        Box::new(self.drop_disc(action.0))
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.winner.is_some() || (0..COLUMNS).all(|column| self.height(column) == ROWS)
+    }
+
+    fn utility(&self, player: Player) -> i32 {
+        match self.winner {
+            Some(winner) if winner == player => WIN,
+            Some(_) => -WIN,
+            None => 0,
+        }
+    }
+
+    fn evaluate(&self, player: Player) -> i32 {
+        if self.is_terminal() {
+            return self.utility(player);
+        }
+
+        self.windows()
+            .iter()
+            .map(|window| {
+                let count = |p| {
+                    window
+                        .iter()
+                        .filter(|&&(r, c)| self.board[r][c] == Some(p))
+                        .count()
+                };
+                match (count(player), count(opponent(player))) {
+                    (mine, 0) => WINDOW_SCORES[mine],
+                    (0, theirs) => -WINDOW_SCORES[theirs],
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+
+    fn as_string(&self) -> String {
+        self.board
+            .iter()
+            .rev()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.map_or('.', |player| MARKS[player]))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        format!("{}{}", MARKS[self.to_move], action.0 + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discs_fall_to_the_bottom() {
+        let game = ConnectFour::from_moves(&[3, 3, 4]).unwrap();
+
+        assert_eq!(
+            "...\n...\n...\n...\n...O...\n...XX..",
+            game.as_string().replace(".......", "...")
+        );
+        assert_eq!(1, game.to_move());
+    }
+
+    #[test]
+    fn test_four_in_a_row_wins() {
+        let horizontal = ConnectFour::from_moves(&[0, 0, 1, 1, 2, 2, 3]).unwrap();
+        let diagonal = ConnectFour::from_moves(&[0, 1, 1, 2, 2, 3, 2, 3, 3, 6, 3]).unwrap();
+
+        assert_eq!(WIN, horizontal.utility(0));
+        assert!(horizontal.actions().is_empty());
+        assert_eq!(-WIN, diagonal.utility(1));
+    }
+
+    #[test]
+    fn test_full_column_can_not_be_played() {
+        let game = ConnectFour::from_moves(&[0, 0, 0, 0, 0, 0]).unwrap();
+
+        assert!(!game.actions().contains(&Action(0)));
+        assert!(ConnectFour::from_moves(&[0, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_evaluation_prefers_open_threes() {
+        let game = ConnectFour::from_moves(&[3, 0, 3, 6, 3]).unwrap();
+
+        assert!(game.evaluate(0) > 0);
+        assert_eq!(-game.evaluate(0), game.evaluate(1));
+    }
+}
@@ -0,0 +1,322 @@
+//! Monte Carlo tree search
+//! Grows a tree from the current state: UCT selects the path down the tree, one new child
+//! is expanded, a random rollout plays the game to the end and the result is backed up.
+//! The most visited action at the root is played.
+//! source:
+//! AIMA 4th edition, chapter 5.4, figure 5.11
+//! https://github.com/aimacode/aima-python/blob/master/games4e.py
+
+use std::time::{Duration, Instant};
+
+use crate::games::{Game, Player};
+use crate::random::Random;
+use crate::search::Action;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Budget {
+    /// number of selection, expansion, rollout and backup cycles
+    Iterations(usize),
+    /// iterate until the time is up, at least once
+    Time(Duration),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MctsConfig {
+    pub budget: Budget,
+    /// weight of the exploration term of UCT, sqrt(2) in theory
+    pub exploration: f64,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        MctsConfig {
+            budget: Budget::Iterations(1000),
+            exploration: std::f64::consts::SQRT_2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionStats {
+    pub action: Action,
+    pub visits: usize,
+    /// share of the rollouts through this action won by the player to move, draws count half
+    pub win_rate: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MctsResult {
+    /// most visited action, None if the game is over
+    pub action: Option<Action>,
+    pub iterations: usize,
+    /// number of nodes in the tree
+    pub nodes: usize,
+    /// statistics of every action at the root, in the order of `Game::actions`
+    pub children: Vec<ActionStats>,
+}
+
+struct Node {
+    // None for the root, which borrows the state the search started from
+    state: Option<Box<dyn Game>>,
+    parent: Option<usize>,
+    action: Action,
+    children: Vec<usize>,
+    untried: Vec<Action>,
+    visits: usize,
+    // sum of rollout rewards for the player who moved into this node
+    reward: f64,
+}
+
+pub fn monte_carlo_tree_search(
+    state: &dyn Game,
+    config: &MctsConfig,
+    rng: &mut Random,
+) -> MctsResult {
+    let mut search = Mcts {
+        root: state,
+        tree: vec![Node {
+            state: None,
+            parent: None,
+            action: Action::none(),
+            children: vec![],
+            untried: if state.is_terminal() {
+                vec![]
+            } else {
+                state.actions()
+            },
+            visits: 0,
+            reward: 0.0,
+        }],
+    };
+
+    let started = Instant::now();
+    let mut iterations = 0;
+    while !search.tree[0].untried.is_empty() || !search.tree[0].children.is_empty() {
+        let done = match config.budget {
+            Budget::Iterations(limit) => iterations >= limit,
+            Budget::Time(limit) => iterations > 0 && started.elapsed() >= limit,
+        };
+        if done {
+            break;
+        }
+
+        let leaf = search.select(config.exploration);
+        let node = search.expand(leaf, rng);
+        let winner = rollout(search.state(node), rng);
+        search.backup(node, winner);
+        iterations += 1;
+    }
+
+    let tree = &search.tree;
+    let mut children: Vec<ActionStats> = tree[0]
+        .children
+        .iter()
+        .map(|&child| ActionStats {
+            action: tree[child].action,
+            visits: tree[child].visits,
+            win_rate: tree[child].reward / tree[child].visits as f64,
+        })
+        .collect();
+    let actions = state.actions();
+    children.sort_by_key(|stats| actions.iter().position(|a| *a == stats.action));
+
+    MctsResult {
+        // ties go to the first action
+        action: children
+            .iter()
+            .rev()
+            .max_by_key(|stats| stats.visits)
+            .map(|stats| stats.action),
+        iterations,
+        nodes: tree.len(),
+        children,
+    }
+}
+
+struct Mcts<'a> {
+    root: &'a dyn Game,
+    tree: Vec<Node>,
+}
+
+impl<'a> Mcts<'a> {
+    fn state(&self, node: usize) -> &dyn Game {
+        self.tree[node].state.as_deref().unwrap_or(self.root)
+    }
+
+    // follows the child with the best UCT value until a node with untried actions or a terminal one
+    fn select(&self, exploration: f64) -> usize {
+        let tree = &self.tree;
+        let mut node = 0;
+
+        while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+            let log_visits = (tree[node].visits as f64).ln();
+            let uct = |child: usize| {
+                let visits = tree[child].visits as f64;
+                tree[child].reward / visits + exploration * (log_visits / visits).sqrt()
+            };
+            node = *tree[node]
+                .children
+                .iter()
+                .max_by(|&&a, &&b| uct(a).total_cmp(&uct(b)))
+                .unwrap();
+        }
+
+        node
+    }
+
+    fn expand(&mut self, node: usize, rng: &mut Random) -> usize {
+        if self.tree[node].untried.is_empty() {
+            return node;
+        }
+
+        let i = rng.gen_range(self.tree[node].untried.len());
+        let action = self.tree[node].untried.swap_remove(i);
+        let state = self.state(node).result(&action);
+
+        self.tree.push(Node {
+            untried: if state.is_terminal() {
+                vec![]
+            } else {
+                state.actions()
+            },
+            state: Some(state),
+            parent: Some(node),
+            action,
+            children: vec![],
+            visits: 0,
+            reward: 0.0,
+        });
+        let child = self.tree.len() - 1;
+        self.tree[node].children.push(child);
+
+        child
+    }
+
+    fn backup(&mut self, mut node: usize, winner: Option<Player>) {
+        loop {
+            let parent = self.tree[node].parent;
+            let reward = match (parent, winner) {
+                (_, None) => 0.5,
+                (Some(parent), Some(winner)) if self.state(parent).to_move() == winner => 1.0,
+                _ => 0.0,
+            };
+            self.tree[node].visits += 1;
+            self.tree[node].reward += reward;
+
+            match parent {
+                Some(parent) => node = parent,
+                None => return,
+            }
+        }
+    }
+}
+
+// plays random moves to the end of the game, returns the winner or None for a draw
+fn rollout(state: &dyn Game, rng: &mut Random) -> Option<Player> {
+    let mut current: Option<Box<dyn Game>> = None;
+
+    loop {
+        let state = current.as_deref().unwrap_or(state);
+        let actions = state.actions();
+        if state.is_terminal() || actions.is_empty() {
+            return match state.utility(0) {
+                u if u > 0 => Some(0),
+                u if u < 0 => Some(1),
+                _ => None,
+            };
+        }
+
+        current = Some(state.result(rng.choose(&actions).unwrap()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::adversarial::alpha_beta_cutoff_search;
+    use crate::games::connect_four::ConnectFour;
+    use crate::games::tic_tac_toe::TicTacToe;
+
+    fn iterations(n: usize) -> MctsConfig {
+        MctsConfig {
+            budget: Budget::Iterations(n),
+            ..MctsConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_mcts_takes_the_winning_move() {
+        let game = TicTacToe::parse("XX.OO....").unwrap();
+        let res = monte_carlo_tree_search(&game, &iterations(500), &mut Random::new(1));
+
+        assert_eq!(Some(Action(2)), res.action);
+    }
+
+    #[test]
+    fn test_mcts_blocks_the_opponent() {
+        let game = TicTacToe::parse(".X..X...O").unwrap();
+        let res = monte_carlo_tree_search(&game, &iterations(2000), &mut Random::new(7));
+
+        assert_eq!(Some(Action(7)), res.action);
+    }
+
+    #[test]
+    fn test_root_visits_add_up_to_iterations() {
+        let game = TicTacToe::new();
+        let res = monte_carlo_tree_search(&game, &iterations(300), &mut Random::new(3));
+        let visits: usize = res.children.iter().map(|stats| stats.visits).sum();
+
+        assert_eq!(300, res.iterations);
+        assert_eq!(300, visits);
+        assert_eq!(301, res.nodes);
+        assert_eq!(
+            game.actions(),
+            res.children
+                .iter()
+                .map(|stats| stats.action)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_tree() {
+        let game = ConnectFour::from_moves(&[3, 3]).unwrap();
+        let first = monte_carlo_tree_search(&game, &iterations(200), &mut Random::new(5));
+        let second = monte_carlo_tree_search(&game, &iterations(200), &mut Random::new(5));
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_finished_game_has_no_action() {
+        let game = TicTacToe::parse("XXXOO....").unwrap();
+        let res = monte_carlo_tree_search(&game, &iterations(100), &mut Random::new(1));
+
+        assert_eq!(None, res.action);
+        assert_eq!(0, res.iterations);
+        assert!(res.children.is_empty());
+    }
+
+    #[test]
+    fn test_time_budget_runs_at_least_once() {
+        let game = ConnectFour::new();
+        let config = MctsConfig {
+            budget: Budget::Time(Duration::from_millis(0)),
+            ..MctsConfig::default()
+        };
+        let res = monte_carlo_tree_search(&game, &config, &mut Random::new(1));
+
+        assert_eq!(1, res.iterations);
+        assert!(res.action.is_some());
+    }
+
+    #[test]
+    fn test_mcts_and_alpha_beta_agree_on_connect_four_win() {
+        let game = ConnectFour::from_moves(&[3, 0, 3, 0, 3, 1]).unwrap();
+        let mcts = monte_carlo_tree_search(&game, &iterations(1000), &mut Random::new(2));
+        let alpha_beta = alpha_beta_cutoff_search(&game, 4, true);
+
+        assert_eq!(Some(Action(3)), mcts.action);
+        assert_eq!(alpha_beta.action, mcts.action);
+    }
+}
@@ -0,0 +1,172 @@
+//! Tic-tac-toe
+//! X (player 0) starts; a win scores 1, a loss -1 and a draw 0.
+//! Boards are written as 9 characters row by row, `X`, `O` and `.` for an empty cell.
+
+use crate::games::{opponent, Game, Player};
+use crate::search::Action;
+
+const CELLS: usize = 9;
+const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+const MARKS: [char; 2] = ['X', 'O'];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TicTacToe {
+    board: [Option<Player>; CELLS],
+    to_move: Player,
+}
+
+impl Default for TicTacToe {
+    fn default() -> Self {
+        TicTacToe::new()
+    }
+}
+
+impl TicTacToe {
+    pub fn new() -> Self {
+        TicTacToe {
+            board: [None; CELLS],
+            to_move: 0,
+        }
+    }
+
+    /// the player to move follows from the number of marks, X moves first
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let cells: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+        if cells.len() != CELLS {
+            return Err(format!("expected {} cells but got {}", CELLS, cells.len()));
+        }
+
+        let mut game = TicTacToe::new();
+        for (i, &c) in cells.iter().enumerate() {
+            game.board[i] = match c.to_ascii_uppercase() {
+                'X' => Some(0),
+                'O' => Some(1),
+                '.' | '-' => None,
+                _ => return Err(format!("unknown cell {:?}", c)),
+            };
+        }
+
+        let count = |player| game.board.iter().filter(|&&c| c == Some(player)).count();
+        game.to_move = match count(0) as i32 - count(1) as i32 {
+            0 => 0,
+            1 => 1,
+            _ => return Err("X moves first and players take turns".to_string()),
+        };
+
+        Ok(game)
+    }
+
+    pub fn winner(&self) -> Option<Player> {
+        LINES.iter().find_map(|line| {
+            let first = self.board[line[0]]?;
+            if line.iter().all(|&cell| self.board[cell] == Some(first)) {
+                Some(first)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl Game for TicTacToe {
+    fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        if self.winner().is_some() {
+            return vec![];
+        }
+
+        (0..CELLS)
+            .filter(|&cell| self.board[cell].is_none())
+            .map(Action)
+            .collect()
+    }
+
+    fn result(&self, action: &Action) -> Box<dyn Game> {
+        let mut next = self.clone();
+
+        next.board[action.0] = Some(self.to_move);
+        next.to_move = opponent(self.to_move);
+        Box::new(next)
+    }
+
+    fn is_terminal(&self) -> bool {
+        self.winner().is_some() || self.board.iter().all(|cell| cell.is_some())
+    }
+
+    fn utility(&self, player: Player) -> i32 {
+        match self.winner() {
+            Some(winner) if winner == player => 1,
+            Some(_) => -1,
+            None => 0,
+        }
+    }
+
+    fn as_string(&self) -> String {
+        self.board
+            .chunks(3)
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.map_or('.', |player| MARKS[player]))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        format!("{}{}", MARKS[self.to_move], action.0 + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_finds_player_to_move() {
+        let game = TicTacToe::parse("X.. .O. ..X").unwrap();
+
+        assert_eq!(1, game.to_move());
+        assert_eq!("X..\n.O.\n..X", game.as_string());
+        assert_eq!(6, game.actions().len());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_boards() {
+        assert!(TicTacToe::parse("XX.......").is_err());
+        assert!(TicTacToe::parse("O........").is_err());
+        assert!(TicTacToe::parse("X.......").is_err());
+        assert!(TicTacToe::parse("X.......?").is_err());
+    }
+
+    #[test]
+    fn test_diagonal_wins_the_game() {
+        let game = TicTacToe::parse("XO.OX...X").unwrap();
+
+        assert!(game.is_terminal());
+        assert!(game.actions().is_empty());
+        assert_eq!(Some(0), game.winner());
+        assert_eq!(1, game.utility(0));
+        assert_eq!(-1, game.utility(1));
+    }
+
+    #[test]
+    fn test_full_board_without_line_is_draw() {
+        let game = TicTacToe::parse("XOXXOOOXX").unwrap();
+
+        assert!(game.is_terminal());
+        assert_eq!(0, game.utility(0));
+    }
+}
@@ -0,0 +1,204 @@
+//! Random solvable boards with controlled difficulty
+//! Boards come from a uniform random permutation that can reach the goal, from a random walk
+//! of the blank starting at the goal, or from a distance oracle that knows the optimal number
+//! of moves of every board. The oracle is a breadth-first search backwards from the goal
+//! over all 9!/2 = 181440 reachable boards.
+//! source:
+//! AIMA 3rd edition, chapter 3.6.3 Generating admissible heuristics from subproblems: Pattern databases
+
+use std::collections::VecDeque;
+
+use crate::eight_puzzle::{EightPuzzleState, PuzzleStateRow, TileDirection, PUZZLE_SIZE};
+use crate::random::Random;
+
+// 9! permutations, half of them can reach a given goal
+const PERMUTATIONS: usize = 362_880;
+const UNKNOWN: u8 = u8::MAX;
+
+/// uniformly random board that can reach the goal; for the default goal these are
+/// the boards passing `EightPuzzleState::is_solveable`
+pub fn random_permutation(goal: PuzzleStateRow, rng: &mut Random) -> PuzzleStateRow {
+    let goal = EightPuzzleState::new(goal);
+    let mut row: PuzzleStateRow = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+
+    loop {
+        rng.shuffle(&mut row);
+        if EightPuzzleState::new(row).can_reach(&goal) {
+            return row;
+        }
+    }
+}
+
+/// board after the blank makes the given number of random moves from the goal;
+/// a move never undoes the previous one, still the board may be closer than `moves`
+pub fn random_walk(goal: PuzzleStateRow, moves: usize, rng: &mut Random) -> PuzzleStateRow {
+    let mut state = EightPuzzleState::new(goal);
+    let mut previous = TileDirection::None;
+
+    for _ in 0..moves {
+        let actions: Vec<TileDirection> = state
+            .possible_actions()
+            .into_iter()
+            .filter(|action| *action != previous.opposite())
+            .collect();
+        let action = *rng.choose(&actions).unwrap();
+        state = state.next_state(&action);
+        previous = action;
+    }
+
+    *state.value()
+}
+
+/// optimal number of moves from every board to the goal
+pub struct DistanceOracle {
+    goal: PuzzleStateRow,
+    /// distance of every permutation indexed by its rank, `UNKNOWN` for unreachable ones
+    distances: Vec<u8>,
+    /// boards grouped by their distance, the goal is the only board of level 0
+    levels: Vec<Vec<PuzzleStateRow>>,
+}
+
+impl DistanceOracle {
+    pub fn new(goal: PuzzleStateRow) -> Self {
+        let mut distances = vec![UNKNOWN; PERMUTATIONS];
+        let mut levels: Vec<Vec<PuzzleStateRow>> = vec![];
+        let mut frontier = VecDeque::new();
+
+        distances[rank(&goal)] = 0;
+        frontier.push_back(EightPuzzleState::new(goal));
+
+        while let Some(state) = frontier.pop_front() {
+            let distance = distances[rank(state.value())];
+            if levels.len() <= distance as usize {
+                levels.push(vec![]);
+            }
+            levels[distance as usize].push(*state.value());
+
+            for action in state.possible_actions().iter() {
+                let next = state.next_state(action);
+                let index = rank(next.value());
+                if distances[index] == UNKNOWN {
+                    distances[index] = distance + 1;
+                    frontier.push_back(next);
+                }
+            }
+        }
+
+        DistanceOracle {
+            goal,
+            distances,
+            levels,
+        }
+    }
+
+    pub fn goal(&self) -> &PuzzleStateRow {
+        &self.goal
+    }
+
+    /// optimal number of moves to the goal, None if the board can not reach it
+    pub fn distance(&self, row: &PuzzleStateRow) -> Option<usize> {
+        match self.distances[rank(row)] {
+            UNKNOWN => None,
+            distance => Some(distance as usize),
+        }
+    }
+
+    /// number of moves of the hardest boards
+    pub fn max_distance(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// all boards exactly the given number of moves from the goal
+    pub fn boards_at(&self, distance: usize) -> &[PuzzleStateRow] {
+        self.levels.get(distance).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn random_at(&self, distance: usize, rng: &mut Random) -> Option<PuzzleStateRow> {
+        rng.choose(self.boards_at(distance)).copied()
+    }
+}
+
+// position of the permutation in lexicographic order, via its Lehmer code
+fn rank(row: &PuzzleStateRow) -> usize {
+    let mut rank = 0;
+
+    for i in 0..PUZZLE_SIZE {
+        let smaller = row[i + 1..].iter().filter(|&&tile| tile < row[i]).count();
+        rank = rank * (PUZZLE_SIZE - i) + smaller;
+    }
+
+    rank
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eight_puzzle::DEFAULT_GOAL;
+
+    #[test]
+    fn test_rank_is_a_bijection() {
+        assert_eq!(0, rank(&[0, 1, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(PERMUTATIONS - 1, rank(&[8, 7, 6, 5, 4, 3, 2, 1, 0]));
+        assert_eq!(1, rank(&[0, 1, 2, 3, 4, 5, 6, 8, 7]));
+    }
+
+    #[test]
+    fn test_random_permutation_is_solvable_and_reproducible() {
+        let mut rng = Random::new(7);
+        let boards: Vec<PuzzleStateRow> = (0..20)
+            .map(|_| random_permutation(DEFAULT_GOAL, &mut rng))
+            .collect();
+
+        assert!(boards
+            .iter()
+            .all(|board| EightPuzzleState::new(*board).is_solveable()));
+        assert_eq!(
+            boards[0],
+            random_permutation(DEFAULT_GOAL, &mut Random::new(7))
+        );
+    }
+
+    #[test]
+    fn test_random_walk_stays_within_its_length() {
+        let oracle = DistanceOracle::new(DEFAULT_GOAL);
+        let mut rng = Random::new(1);
+
+        assert_eq!(DEFAULT_GOAL, random_walk(DEFAULT_GOAL, 0, &mut rng));
+        assert_eq!(
+            Some(1),
+            oracle.distance(&random_walk(DEFAULT_GOAL, 1, &mut rng))
+        );
+        for _ in 0..10 {
+            let distance = oracle.distance(&random_walk(DEFAULT_GOAL, 12, &mut rng));
+            assert!(distance.is_some_and(|d| d <= 12 && d % 2 == 0));
+        }
+    }
+
+    #[test]
+    fn test_oracle_knows_every_reachable_board() {
+        let oracle = DistanceOracle::new(DEFAULT_GOAL);
+        let reachable: usize = (0..=oracle.max_distance())
+            .map(|d| oracle.boards_at(d).len())
+            .sum();
+
+        assert_eq!(PERMUTATIONS / 2, reachable);
+        assert_eq!(31, oracle.max_distance());
+        assert_eq!(2, oracle.boards_at(31).len());
+        assert_eq!(Some(31), oracle.distance(&[8, 6, 7, 2, 5, 4, 3, 0, 1]));
+        assert_eq!(Some(22), oracle.distance(&[0, 1, 2, 3, 4, 5, 6, 7, 8]));
+        assert_eq!(None, oracle.distance(&[2, 1, 3, 4, 5, 6, 7, 8, 0]));
+        assert!(oracle.boards_at(32).is_empty());
+    }
+
+    #[test]
+    fn test_random_board_at_exact_distance() {
+        let goal = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+        let oracle = DistanceOracle::new(goal);
+        let mut rng = Random::new(3);
+        let board = oracle.random_at(10, &mut rng).unwrap();
+
+        assert_eq!(&goal, oracle.goal());
+        assert_eq!(Some(10), oracle.distance(&board));
+        assert_eq!(None, oracle.random_at(40, &mut rng));
+    }
+}
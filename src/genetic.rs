@@ -0,0 +1,348 @@
+//! Genetic algorithm for 8slide puzzle
+//! Individuals are fixed-length sequences of blank moves; the fitness of an individual is
+//! the heuristic value of the board after applying its moves, so lower is better.
+//! Moves that would push the blank off the board are skipped, and the sequence ends
+//! early if it reaches the goal.
+//! source:
+//! AIMA 3rd edition, chapter 4.1.4 Genetic algorithms
+
+use crate::eight_puzzle::{EightPuzzle, EightPuzzleState, Heuristic, TileDirection, DEFAULT_GOAL};
+use crate::random::Random;
+
+const GENES: [TileDirection; 4] = [
+    TileDirection::Up,
+    TileDirection::Down,
+    TileDirection::Left,
+    TileDirection::Right,
+];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Selection {
+    /// picks the best of n randomly chosen individuals
+    Tournament(usize),
+    /// picks individuals with probability proportional to 1 / (1 + fitness)
+    Roulette,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Crossover {
+    /// child takes the prefix of one parent and the suffix of the other
+    SinglePoint,
+    /// every move is taken from a randomly chosen parent
+    Uniform,
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneticConfig {
+    pub population_size: usize,
+    pub sequence_length: usize,
+    pub generations: usize,
+    pub selection: Selection,
+    pub crossover: Crossover,
+    /// probability that two parents are combined, otherwise the first parent is copied
+    pub crossover_rate: f64,
+    /// probability that a single move is replaced by a random move
+    pub mutation_rate: f64,
+    /// number of best individuals copied unchanged into the next generation
+    pub elitism: usize,
+    pub heuristic: Heuristic,
+    pub seed: u64,
+}
+
+impl Default for GeneticConfig {
+    fn default() -> Self {
+        GeneticConfig {
+            population_size: 200,
+            sequence_length: 30,
+            generations: 500,
+            selection: Selection::Tournament(3),
+            crossover: Crossover::SinglePoint,
+            crossover_rate: 0.9,
+            mutation_rate: 0.05,
+            elitism: 2,
+            heuristic: Heuristic::ManhattanDistance,
+            seed: 42,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Individual {
+    pub moves: Vec<TileDirection>,
+    /// heuristic value of the final board, 0 means the goal was reached
+    pub fitness: u32,
+    /// moves that were actually applied, without skipped moves
+    pub solution: Vec<TileDirection>,
+}
+
+impl Individual {
+    pub fn new(
+        initial: &EightPuzzleState,
+        moves: Vec<TileDirection>,
+        heuristic: Heuristic,
+    ) -> Self {
+        let (state, solution) = apply_moves(initial, &moves);
+        let fitness = EightPuzzle::new(state).with_heuristic(heuristic).h() as u32;
+
+        Individual {
+            moves,
+            fitness,
+            solution,
+        }
+    }
+
+    pub fn is_solution(&self) -> bool {
+        self.fitness == 0
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub best_fitness: u32,
+    pub mean_fitness: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneticResult {
+    pub best: Individual,
+    pub history: Vec<GenerationStats>,
+}
+
+impl GeneticResult {
+    pub fn generations(&self) -> usize {
+        self.history.len()
+    }
+}
+
+/// Evolves the population until some individual solves the puzzle
+/// or the number of generations runs out.
+pub fn genetic_algorithm(initial: &EightPuzzleState, config: &GeneticConfig) -> GeneticResult {
+    assert!(config.population_size > 0, "population must not be empty");
+
+    let mut rng = Random::new(config.seed);
+    let mut population: Vec<Individual> = (0..config.population_size)
+        .map(|_| {
+            let moves = (0..config.sequence_length)
+                .map(|_| random_gene(&mut rng))
+                .collect();
+            Individual::new(initial, moves, config.heuristic)
+        })
+        .collect();
+    let mut history = vec![];
+
+    for generation in 0..config.generations {
+        population.sort_by_key(|individual| individual.fitness);
+        history.push(generation_stats(generation, &population));
+
+        if population[0].is_solution() {
+            break;
+        }
+
+        let mut next_population: Vec<Individual> =
+            population.iter().take(config.elitism).cloned().collect();
+
+        while next_population.len() < config.population_size {
+            let mother = select(&population, config.selection, &mut rng);
+            let father = select(&population, config.selection, &mut rng);
+
+            let mut moves = if rng.gen_bool(config.crossover_rate) {
+                crossover(&mother.moves, &father.moves, config.crossover, &mut rng)
+            } else {
+                mother.moves.clone()
+            };
+            mutate(&mut moves, config.mutation_rate, &mut rng);
+
+            next_population.push(Individual::new(initial, moves, config.heuristic));
+        }
+
+        population = next_population;
+    }
+
+    population.sort_by_key(|individual| individual.fitness);
+    GeneticResult {
+        best: population.swap_remove(0),
+        history,
+    }
+}
+
+/// applies moves until the goal is reached, moves off the board are skipped;
+/// returns the final state and the moves that were applied
+pub fn apply_moves(
+    initial: &EightPuzzleState,
+    moves: &[TileDirection],
+) -> (EightPuzzleState, Vec<TileDirection>) {
+    let mut state = initial.clone();
+    let mut applied = vec![];
+
+    for direction in moves.iter() {
+        if state.value() == &DEFAULT_GOAL {
+            break;
+        }
+        if state.possible_actions().contains(direction) {
+            state = state.next_state(direction);
+            applied.push(*direction);
+        }
+    }
+
+    (state, applied)
+}
+
+fn random_gene(rng: &mut Random) -> TileDirection {
+    GENES[rng.gen_range(GENES.len())]
+}
+
+fn select<'a>(
+    population: &'a [Individual],
+    selection: Selection,
+    rng: &mut Random,
+) -> &'a Individual {
+    match selection {
+        Selection::Tournament(size) => (0..size.max(1))
+            .map(|_| &population[rng.gen_range(population.len())])
+            .min_by_key(|individual| individual.fitness)
+            .unwrap(),
+        Selection::Roulette => {
+            let weights: Vec<f64> = population
+                .iter()
+                .map(|individual| 1.0 / (1.0 + individual.fitness as f64))
+                .collect();
+            let mut pick = rng.gen_f64() * weights.iter().sum::<f64>();
+
+            for (individual, weight) in population.iter().zip(weights.iter()) {
+                if pick < *weight {
+                    return individual;
+                }
+                pick -= weight;
+            }
+            population.last().unwrap()
+        }
+    }
+}
+
+fn crossover(
+    mother: &[TileDirection],
+    father: &[TileDirection],
+    crossover: Crossover,
+    rng: &mut Random,
+) -> Vec<TileDirection> {
+    match crossover {
+        Crossover::SinglePoint => {
+            let point = rng.gen_range(mother.len() + 1);
+            mother[..point]
+                .iter()
+                .chain(father[point..].iter())
+                .cloned()
+                .collect()
+        }
+        Crossover::Uniform => mother
+            .iter()
+            .zip(father.iter())
+            .map(|(&m, &f)| if rng.gen_bool(0.5) { m } else { f })
+            .collect(),
+    }
+}
+
+fn mutate(moves: &mut [TileDirection], mutation_rate: f64, rng: &mut Random) {
+    for direction in moves.iter_mut() {
+        if rng.gen_bool(mutation_rate) {
+            *direction = random_gene(rng);
+        }
+    }
+}
+
+fn generation_stats(generation: usize, population: &[Individual]) -> GenerationStats {
+    let total: u32 = population.iter().map(|individual| individual.fitness).sum();
+
+    GenerationStats {
+        generation,
+        best_fitness: population[0].fitness,
+        mean_fitness: total as f64 / population.len() as f64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_moves_skips_moves_off_the_board() {
+        let initial = EightPuzzleState::new([1, 2, 3, 4, 5, 6, 7, 0, 8]);
+        let moves = vec![TileDirection::Down, TileDirection::Right, TileDirection::Up];
+        let (state, applied) = apply_moves(&initial, &moves);
+
+        assert_eq!(&DEFAULT_GOAL, state.value());
+        assert_eq!(vec![TileDirection::Right], applied);
+    }
+
+    #[test]
+    fn test_individual_fitness_is_heuristic_of_final_board() {
+        let initial = EightPuzzleState::new([1, 2, 3, 4, 0, 5, 7, 8, 6]);
+        let solved = Individual::new(
+            &initial,
+            vec![
+                TileDirection::Right,
+                TileDirection::Down,
+                TileDirection::Left,
+            ],
+            Heuristic::ManhattanDistance,
+        );
+        let unsolved = Individual::new(
+            &initial,
+            vec![TileDirection::Up],
+            Heuristic::ManhattanDistance,
+        );
+
+        assert!(solved.is_solution());
+        assert_eq!(
+            vec![TileDirection::Right, TileDirection::Down],
+            solved.solution
+        );
+        assert_eq!(3, unsolved.fitness);
+    }
+
+    #[test]
+    fn test_crossover_keeps_sequence_length() {
+        let mut rng = Random::new(1);
+        let mother = vec![TileDirection::Up; 10];
+        let father = vec![TileDirection::Down; 10];
+
+        for kind in [Crossover::SinglePoint, Crossover::Uniform].iter() {
+            let child = crossover(&mother, &father, *kind, &mut rng);
+            assert_eq!(10, child.len());
+        }
+    }
+
+    #[test]
+    fn test_genetic_algorithm_solves_easy_board() {
+        let initial = EightPuzzleState::new([1, 2, 3, 7, 4, 5, 0, 8, 6]);
+        let config = GeneticConfig {
+            population_size: 50,
+            sequence_length: 8,
+            generations: 100,
+            ..GeneticConfig::default()
+        };
+        let res = genetic_algorithm(&initial, &config);
+
+        assert!(res.best.is_solution());
+        assert!(res.generations() <= 100);
+    }
+
+    #[test]
+    fn test_genetic_algorithm_is_reproducible_with_seed() {
+        let initial = EightPuzzleState::new([4, 1, 3, 7, 2, 6, 5, 8, 0]);
+        let config = GeneticConfig {
+            population_size: 30,
+            generations: 20,
+            selection: Selection::Roulette,
+            crossover: Crossover::Uniform,
+            ..GeneticConfig::default()
+        };
+
+        let first = genetic_algorithm(&initial, &config);
+        let second = genetic_algorithm(&initial, &config);
+
+        assert_eq!(first.best, second.best);
+        assert_eq!(first.history, second.history);
+    }
+}
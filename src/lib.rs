@@ -0,0 +1,9 @@
+pub mod cli;
+pub mod csp;
+pub mod eight_puzzle;
+pub mod games;
+pub mod generator;
+pub mod genetic;
+pub mod problems;
+pub mod random;
+pub mod search;
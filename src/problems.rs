@@ -0,0 +1,11 @@
+//! Problem domains other than 8slide puzzle, used to exercise the search algorithms
+//! on different state spaces and step costs
+
+pub mod hanoi;
+pub mod missionaries;
+pub mod n_queens;
+pub mod pocket_cube;
+pub mod route;
+pub mod sokoban;
+pub mod vacuum;
+pub mod water_jugs;
@@ -0,0 +1,138 @@
+//! Towers of Hanoi
+//! Disks are moved one by one between three pegs, a disk never lies on a smaller one;
+//! the whole tower has to be moved from the first peg to the last one.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::search::{Action, SearchProblem};
+
+pub const PEGS: usize = 3;
+const PEG_NAMES: [char; PEGS] = ['A', 'B', 'C'];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hanoi {
+    // peg of every disk, disk 0 is the smallest
+    disks: Vec<usize>,
+}
+
+impl Hanoi {
+    /// all disks start on the first peg
+    pub fn new(disks: usize) -> Self {
+        Hanoi {
+            disks: vec![0; disks],
+        }
+    }
+
+    // smallest disk on the peg is on the top
+    fn top(&self, peg: usize) -> Option<usize> {
+        self.disks.iter().position(|&p| p == peg)
+    }
+
+    fn can_move(&self, from: usize, to: usize) -> bool {
+        match (self.top(from), self.top(to)) {
+            (Some(_), None) => from != to,
+            (Some(disk), Some(other)) => disk < other,
+            (None, _) => false,
+        }
+    }
+}
+
+// action moves the top disk from peg `action / PEGS` to peg `action % PEGS`
+impl SearchProblem for Hanoi {
+    fn actions(&self) -> Vec<Action> {
+        (0..PEGS * PEGS)
+            .filter(|&i| self.can_move(i / PEGS, i % PEGS))
+            .map(Action)
+            .collect()
+    }
+
+    fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
+        let (from, to) = (action.0 / PEGS, action.0 % PEGS);
+        let disk = self.top(from).expect("peg must not be empty");
+        let mut next = self.clone();
+
+        next.disks[disk] = to;
+        Box::new(next)
+    }
+
+    fn test_goal(&self) -> bool {
+        self.disks.iter().all(|&peg| peg == PEGS - 1)
+    }
+
+    fn path_cost(&self) -> u32 {
+        1
+    }
+
+    // disks that are not on the last peg yet
+    fn value(&self) -> u32 {
+        self.disks.iter().filter(|&&peg| peg != PEGS - 1).count() as u32
+    }
+
+    fn as_string(&self) -> String {
+        let pegs: Vec<String> = (0..PEGS)
+            .map(|peg| {
+                let disks: Vec<String> = (0..self.disks.len())
+                    .rev()
+                    .filter(|&disk| self.disks[disk] == peg)
+                    .map(|disk| (disk + 1).to_string())
+                    .collect();
+                format!("{}: [{}]", PEG_NAMES[peg], disks.join(", "))
+            })
+            .collect();
+
+        format!("Hanoi({})", pegs.join(", "))
+    }
+
+    fn hash_code(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        format!(
+            "{}->{}",
+            PEG_NAMES[action.0 / PEGS],
+            PEG_NAMES[action.0 % PEGS]
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::uninformed::{breadth_first_search, iterative_deepening_search};
+
+    #[test]
+    fn test_tower_of_n_disks_takes_2_pow_n_minus_1_moves() {
+        for disks in 1..=4 {
+            let solution = breadth_first_search(Box::new(Hanoi::new(disks))).unwrap();
+            assert_eq!((1 << disks) - 1, solution.depth());
+        }
+    }
+
+    #[test]
+    fn test_iterative_deepening_moves_three_disks() {
+        let problem = Hanoi::new(3);
+        let solution = iterative_deepening_search(Box::new(problem.clone())).unwrap();
+        let first_move = solution.solution()[1];
+
+        assert_eq!(7, solution.depth());
+        assert_eq!("A->C", problem.action_name(&first_move));
+    }
+
+    #[test]
+    fn test_larger_disk_can_not_go_on_smaller() {
+        let problem = Hanoi { disks: vec![1, 0] };
+        let names: Vec<String> = problem
+            .actions()
+            .iter()
+            .map(|action| problem.action_name(action))
+            .collect();
+
+        assert_eq!(vec!["A->C", "B->A", "B->C"], names);
+        assert_eq!("Hanoi(A: [2], B: [1], C: [])", problem.as_string());
+    }
+}
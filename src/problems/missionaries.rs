@@ -0,0 +1,168 @@
+//! Missionaries and cannibals
+//! Everybody has to cross the river in a boat for at most `capacity` people;
+//! cannibals must never outnumber missionaries on either bank, if there are any missionaries.
+//! source:
+//! AIMA 3rd edition, exercise 3.9
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::search::{Action, SearchProblem};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Missionaries {
+    total: u8,
+    capacity: u8,
+    // people and boat on the starting bank
+    missionaries: u8,
+    cannibals: u8,
+    boat_on_start: bool,
+}
+
+impl Missionaries {
+    /// the classic puzzle with 3 missionaries, 3 cannibals and a boat for two
+    pub fn classic() -> Self {
+        Missionaries::new(3, 2)
+    }
+
+    pub fn new(total: u8, capacity: u8) -> Self {
+        assert!(capacity > 0, "boat must carry somebody");
+
+        Missionaries {
+            total,
+            capacity,
+            missionaries: total,
+            cannibals: total,
+            boat_on_start: true,
+        }
+    }
+
+    /// all boat loads as (missionaries, cannibals), action is the index into this list
+    fn loads(&self) -> Vec<(u8, u8)> {
+        let mut loads = vec![];
+        for m in 0..=self.capacity {
+            for c in 0..=(self.capacity - m) {
+                if m + c > 0 {
+                    loads.push((m, c));
+                }
+            }
+        }
+
+        loads
+    }
+
+    fn is_safe(&self) -> bool {
+        let other_m = self.total - self.missionaries;
+        let other_c = self.total - self.cannibals;
+
+        (self.missionaries == 0 || self.missionaries >= self.cannibals)
+            && (other_m == 0 || other_m >= other_c)
+    }
+
+    fn cross(&self, (m, c): (u8, u8)) -> Option<Missionaries> {
+        let (boat_m, boat_c) = if self.boat_on_start {
+            (self.missionaries, self.cannibals)
+        } else {
+            (self.total - self.missionaries, self.total - self.cannibals)
+        };
+        if m > boat_m || c > boat_c {
+            return None;
+        }
+
+        let mut next = self.clone();
+        if self.boat_on_start {
+            next.missionaries -= m;
+            next.cannibals -= c;
+        } else {
+            next.missionaries += m;
+            next.cannibals += c;
+        }
+        next.boat_on_start = !self.boat_on_start;
+
+        Some(next).filter(|state| state.is_safe())
+    }
+}
+
+impl SearchProblem for Missionaries {
+    fn actions(&self) -> Vec<Action> {
+        self.loads()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, load)| self.cross(load).is_some())
+            .map(|(i, _)| Action(i))
+            .collect()
+    }
+
+    fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
+        let load = self.loads()[action.0];
+        Box::new(self.cross(load).expect("boat load must be legal"))
+    }
+
+    fn test_goal(&self) -> bool {
+        self.missionaries == 0 && self.cannibals == 0 && !self.boat_on_start
+    }
+
+    fn path_cost(&self) -> u32 {
+        1
+    }
+
+    // people still waiting on the starting bank
+    fn value(&self) -> u32 {
+        (self.missionaries + self.cannibals) as u32
+    }
+
+    fn as_string(&self) -> String {
+        format!(
+            "Missionaries(start: {}M {}C, boat: {})",
+            self.missionaries,
+            self.cannibals,
+            if self.boat_on_start { "start" } else { "other" }
+        )
+    }
+
+    fn hash_code(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        let (m, c) = self.loads()[action.0];
+        let arrow = if self.boat_on_start { "->" } else { "<-" };
+
+        format!("{}M{}C {}", m, c, arrow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::uninformed::breadth_first_search;
+
+    #[test]
+    fn test_classic_problem_needs_eleven_crossings() {
+        let solution = breadth_first_search(Box::new(Missionaries::classic())).unwrap();
+
+        assert_eq!(11, solution.depth());
+        assert!(solution.item().test_goal());
+    }
+
+    #[test]
+    fn test_unsafe_loads_are_not_offered() {
+        let problem = Missionaries::classic();
+        let names: Vec<String> = problem
+            .actions()
+            .iter()
+            .map(|action| problem.action_name(action))
+            .collect();
+
+        // missionaries crossing without a cannibal leave the rest outnumbered
+        assert_eq!(vec!["0M1C ->", "0M2C ->", "1M1C ->"], names);
+    }
+
+    #[test]
+    fn test_four_missionaries_and_cannibals_can_not_cross_in_boat_for_two() {
+        assert!(breadth_first_search(Box::new(Missionaries::new(4, 2))).is_none());
+    }
+}
@@ -0,0 +1,131 @@
+//! N-queens, incremental formulation
+//! Queens are placed row by row, only on squares that no queen already placed attacks;
+//! a board is a dead end if the next row has no such square left.
+//! source:
+//! AIMA 3rd edition, chapter 3.2.1
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::search::{Action, SearchProblem};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NQueens {
+    size: usize,
+    // column of the queen in every row placed so far
+    queens: Vec<usize>,
+}
+
+impl NQueens {
+    /// starts with an empty board
+    pub fn new(size: usize) -> Self {
+        NQueens {
+            size,
+            queens: vec![],
+        }
+    }
+
+    pub fn queens(&self) -> &[usize] {
+        &self.queens
+    }
+
+    fn is_attacked(&self, column: usize) -> bool {
+        let row = self.queens.len();
+
+        self.queens
+            .iter()
+            .enumerate()
+            .any(|(r, &c)| c == column || row - r == c.abs_diff(column))
+    }
+}
+
+// action is the column of the queen in the next row
+impl SearchProblem for NQueens {
+    fn actions(&self) -> Vec<Action> {
+        if self.queens.len() == self.size {
+            return vec![];
+        }
+
+        (0..self.size)
+            .filter(|&column| !self.is_attacked(column))
+            .map(Action)
+            .collect()
+    }
+
+    fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
+        let mut next = self.clone();
+
+        next.queens.push(action.0);
+        Box::new(next)
+    }
+
+    fn test_goal(&self) -> bool {
+        self.queens.len() == self.size
+    }
+
+    fn path_cost(&self) -> u32 {
+        1
+    }
+
+    // queens left to place
+    fn value(&self) -> u32 {
+        (self.size - self.queens.len()) as u32
+    }
+
+    fn as_string(&self) -> String {
+        format!("NQueens(size: {}, queens: {:?})", self.size, self.queens)
+    }
+
+    fn hash_code(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        format!("Q{}@{}", self.queens.len() + 1, action.0 + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::uninformed::{breadth_first_search, depth_first_search};
+    use crate::search::SearchNode;
+
+    fn queens_of(node: &SearchNode) -> Vec<usize> {
+        node.solution()[1..].iter().map(|action| action.0).collect()
+    }
+
+    #[test]
+    fn test_depth_first_places_eight_queens() {
+        let solution = depth_first_search(Box::new(NQueens::new(8))).unwrap();
+        let queens = queens_of(&solution);
+
+        assert_eq!(8, solution.depth());
+        for (r1, &c1) in queens.iter().enumerate() {
+            for (r2, &c2) in queens.iter().enumerate().skip(r1 + 1) {
+                assert_ne!(c1, c2);
+                assert_ne!(r2 - r1, c1.abs_diff(c2));
+            }
+        }
+    }
+
+    #[test]
+    fn test_three_queens_have_no_solution() {
+        assert!(depth_first_search(Box::new(NQueens::new(3))).is_none());
+        assert!(breadth_first_search(Box::new(NQueens::new(2))).is_none());
+    }
+
+    #[test]
+    fn test_attacked_squares_are_not_offered() {
+        let problem = NQueens {
+            size: 4,
+            queens: vec![1],
+        };
+
+        assert_eq!(vec![Action(3)], problem.actions());
+        assert_eq!("Q2@4", problem.action_name(&Action(3)));
+    }
+}
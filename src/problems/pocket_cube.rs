@@ -0,0 +1,616 @@
+//! Pocket cube, the 2x2x2 Rubik's cube
+//! The down-back-left corner never moves, so only U, R and F faces are turned;
+//! this leaves 7! * 3^6 = 3 674 160 states, all solvable in at most 11 face turns.
+//!
+//! Facelets are written face by face in order U, R, F, D, L, B, every face read row by row
+//! as on the unfolded cube: U with B on the top, D with F on the top, and R, F, L, B with U on the top.
+//! The solved cube is `UUUURRRRFFFFDDDDLLLLBBBB`; any six characters may be used as colors.
+//!
+//! The heuristic is the maximum of two corner pattern databases,
+//! each stores the exact number of turns needed to solve a subset of corners.
+//! source:
+//! AIMA 3rd edition, chapter 3.6.3 Learning heuristics from subproblems: pattern databases
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::sync::OnceLock;
+
+use crate::search::{Action, SearchProblem};
+
+pub const FACELETS: usize = 24;
+const FACES: [char; 6] = ['U', 'R', 'F', 'D', 'L', 'B'];
+
+const CORNERS: usize = 8;
+// cubie coordinates: x points to R, y to U and z to F
+const CORNER_POSITIONS: [[i8; 3]; CORNERS] = [
+    [1, 1, 1],    // URF
+    [-1, 1, 1],   // UFL
+    [-1, 1, -1],  // ULB
+    [1, 1, -1],   // UBR
+    [1, -1, 1],   // DFR
+    [-1, -1, 1],  // DLF
+    [-1, -1, -1], // DBL - fixed
+    [1, -1, -1],  // DRB
+];
+const FIXED_CORNER: usize = 6;
+
+const TURNED_FACES: [char; 3] = ['U', 'R', 'F'];
+const TURN_SUFFIXES: [&str; 3] = ["", "2", "'"];
+const ACTIONS: usize = 9;
+
+// corners stored by every pattern database, together they cover all moving corners
+const UPPER_CORNERS: [usize; 4] = [0, 1, 2, 3];
+const LOWER_CORNERS: [usize; 3] = [4, 5, 7];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PocketCube {
+    // cubie at every corner position and how many times it is twisted clockwise
+    corners: [u8; CORNERS],
+    twists: [u8; CORNERS],
+}
+
+impl Default for PocketCube {
+    fn default() -> Self {
+        PocketCube::solved()
+    }
+}
+
+impl PocketCube {
+    pub fn solved() -> Self {
+        PocketCube {
+            corners: [0, 1, 2, 3, 4, 5, 6, 7],
+            twists: [0; CORNERS],
+        }
+    }
+
+    /// parses 24 facelet colors, whitespace is ignored
+    pub fn from_facelets(text: &str) -> Result<Self, String> {
+        let colors: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+        if colors.len() != FACELETS {
+            return Err(format!(
+                "expected {} facelets but got {}",
+                FACELETS,
+                colors.len()
+            ));
+        }
+
+        let mut palette: Vec<char> = vec![];
+        for &color in colors.iter() {
+            if !palette.contains(&color) {
+                palette.push(color);
+            }
+        }
+        for color in palette.iter() {
+            let count = colors.iter().filter(|&c| c == color).count();
+            if count != 4 {
+                return Err(format!(
+                    "color {} appears {} times, expected 4",
+                    color, count
+                ));
+            }
+        }
+        if palette.len() != FACES.len() {
+            return Err(format!("expected 6 colors but got {}", palette.len()));
+        }
+
+        let faces = faces_of_colors(&colors)?;
+        let tables = tables();
+        let mut cube = PocketCube {
+            corners: [0; CORNERS],
+            twists: [0; CORNERS],
+        };
+        let mut seen = [false; CORNERS];
+
+        for position in 0..CORNERS {
+            let sticker_faces: Vec<usize> = tables.facelets[position]
+                .iter()
+                .map(|&s| faces[s])
+                .collect();
+            let (cubie, twist) = identify_corner(&tables.facelets, &sticker_faces)
+                .ok_or_else(|| format!("corner {} has impossible colors", position + 1))?;
+
+            if seen[cubie] {
+                return Err(format!("corner {} appears twice", position + 1));
+            }
+            seen[cubie] = true;
+            cube.corners[position] = cubie as u8;
+            cube.twists[position] = twist;
+        }
+
+        if cube.twists.iter().map(|&t| t as u32).sum::<u32>() % 3 != 0 {
+            return Err("corners are twisted, the cube can not be solved".to_string());
+        }
+
+        Ok(cube)
+    }
+
+    /// applies turns written like `R U2 F'`
+    pub fn apply(&self, moves: &str) -> Result<Self, String> {
+        let mut cube = self.clone();
+        for token in moves.split_whitespace() {
+            cube = cube.turn(parse_turn(token)?);
+        }
+
+        Ok(cube)
+    }
+
+    /// facelets with face letters as colors
+    pub fn facelets(&self) -> String {
+        let tables = tables();
+        let mut facelets = ['?'; FACELETS];
+
+        for position in 0..CORNERS {
+            let cubie = self.corners[position] as usize;
+            let twist = self.twists[position] as usize;
+            for slot in 0..3 {
+                let sticker = tables.facelets[position][(slot + twist) % 3];
+                facelets[sticker] = FACES[tables.facelets[cubie][slot] / 4];
+            }
+        }
+
+        facelets.iter().collect()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == PocketCube::solved()
+    }
+
+    /// lower bound of turns needed to solve the cube
+    pub fn h(&self) -> u8 {
+        let tables = tables();
+
+        tables.upper.distance(self).max(tables.lower.distance(self))
+    }
+
+    fn turn(&self, action: usize) -> Self {
+        tables().moves[action].apply(self)
+    }
+}
+
+impl SearchProblem for PocketCube {
+    fn actions(&self) -> Vec<Action> {
+        (0..ACTIONS).map(Action).collect()
+    }
+
+    fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
+        Box::new(self.turn(action.0))
+    }
+
+    fn test_goal(&self) -> bool {
+        self.is_solved()
+    }
+
+    fn path_cost(&self) -> u32 {
+        1
+    }
+
+    fn value(&self) -> u32 {
+        self.h() as u32
+    }
+
+    fn as_string(&self) -> String {
+        format!("PocketCube({})", self.facelets())
+    }
+
+    fn hash_code(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        format!(
+            "{}{}",
+            TURNED_FACES[action.0 / 3],
+            TURN_SUFFIXES[action.0 % 3]
+        )
+    }
+}
+
+fn parse_turn(token: &str) -> Result<usize, String> {
+    let mut chars = token.chars();
+    let face = chars
+        .next()
+        .and_then(|c| TURNED_FACES.iter().position(|&f| f == c))
+        .ok_or_else(|| format!("unknown turn {}, only U, R and F are turned", token))?;
+    let turns = TURN_SUFFIXES
+        .iter()
+        .position(|&suffix| suffix == chars.as_str())
+        .ok_or_else(|| format!("unknown turn {}", token))?;
+
+    Ok(face * 3 + turns)
+}
+
+/// maps every sticker to the face its color belongs to;
+/// the fixed corner tells the colors of D, L and B, the rest are their opposites
+fn faces_of_colors(colors: &[char]) -> Result<Vec<usize>, String> {
+    let tables = tables();
+    let mut color_faces: Vec<(char, usize)> = tables.facelets[FIXED_CORNER]
+        .iter()
+        .map(|&s| (colors[s], s / 4))
+        .collect();
+
+    for i in 0..3 {
+        let (color, face) = color_faces[i];
+        // opposite colors never meet on the same corner
+        let opposite: Vec<char> = colors
+            .iter()
+            .copied()
+            .filter(|&other| {
+                other != color
+                    && tables.facelets.iter().all(|corner| {
+                        !(corner.iter().any(|&s| colors[s] == color)
+                            && corner.iter().any(|&s| colors[s] == other))
+                    })
+            })
+            .collect();
+
+        match opposite.first() {
+            Some(&other) if opposite.iter().all(|&c| c == other) => {
+                color_faces.push((other, (face + 3) % FACES.len()))
+            }
+            _ => return Err(format!("color {} has no opposite color", color)),
+        }
+    }
+
+    colors
+        .iter()
+        .map(|color| {
+            color_faces
+                .iter()
+                .find(|(c, _)| c == color)
+                .map(|&(_, face)| face)
+                .ok_or_else(|| format!("color {} does not fit the color scheme", color))
+        })
+        .collect()
+}
+
+// finds the cubie with stickers of the given faces and its twist
+fn identify_corner(facelets: &[[usize; 3]; CORNERS], faces: &[usize]) -> Option<(usize, u8)> {
+    // U or D sticker tells the twist, the others must follow in the same clockwise order
+    let twist = faces.iter().position(|&face| face % 3 == 0)?;
+
+    (0..CORNERS)
+        .find(|&cubie| (0..3).all(|slot| faces[(slot + twist) % 3] == facelets[cubie][slot] / 4))
+        .map(|cubie| (cubie, twist as u8))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CornerMove {
+    // position the corner moves to and the clockwise twist it gets on the way
+    target: [usize; CORNERS],
+    twist: [u8; CORNERS],
+}
+
+impl CornerMove {
+    fn apply(&self, cube: &PocketCube) -> PocketCube {
+        let mut next = cube.clone();
+        for position in 0..CORNERS {
+            let target = self.target[position];
+            next.corners[target] = cube.corners[position];
+            next.twists[target] = (cube.twists[position] + self.twist[position]) % 3;
+        }
+
+        next
+    }
+
+    // this move followed by the other one
+    fn then(&self, other: &CornerMove) -> CornerMove {
+        let mut composed = *self;
+        for position in 0..CORNERS {
+            let middle = self.target[position];
+            composed.target[position] = other.target[middle];
+            composed.twist[position] = (self.twist[position] + other.twist[middle]) % 3;
+        }
+
+        composed
+    }
+}
+
+struct PatternDatabase {
+    cubies: Vec<usize>,
+    distances: Vec<u8>,
+}
+
+impl PatternDatabase {
+    /// breadth first search backwards from the solved cube over the positions of `cubies`
+    fn build(cubies: &[usize], moves: &[CornerMove]) -> Self {
+        let size = (CORNERS * 3).pow(cubies.len() as u32);
+        let mut distances = vec![u8::MAX; size];
+        let solved: Vec<(usize, u8)> = cubies.iter().map(|&cubie| (cubie, 0)).collect();
+        let mut queue = VecDeque::new();
+
+        distances[encode(&solved)] = 0;
+        queue.push_back(solved);
+
+        while let Some(pattern) = queue.pop_front() {
+            let distance = distances[encode(&pattern)];
+            for corner_move in moves.iter() {
+                let next: Vec<(usize, u8)> = pattern
+                    .iter()
+                    .map(|&(position, twist)| {
+                        (
+                            corner_move.target[position],
+                            (twist + corner_move.twist[position]) % 3,
+                        )
+                    })
+                    .collect();
+                let index = encode(&next);
+                if distances[index] == u8::MAX {
+                    distances[index] = distance + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        PatternDatabase {
+            cubies: cubies.to_vec(),
+            distances,
+        }
+    }
+
+    fn distance(&self, cube: &PocketCube) -> u8 {
+        let pattern: Vec<(usize, u8)> = self
+            .cubies
+            .iter()
+            .map(|&cubie| {
+                let position = cube
+                    .corners
+                    .iter()
+                    .position(|&c| c as usize == cubie)
+                    .unwrap();
+                (position, cube.twists[position])
+            })
+            .collect();
+
+        self.distances[encode(&pattern)]
+    }
+}
+
+fn encode(pattern: &[(usize, u8)]) -> usize {
+    pattern.iter().fold(0, |index, &(position, twist)| {
+        index * CORNERS * 3 + position * 3 + twist as usize
+    })
+}
+
+struct Tables {
+    // stickers of every corner position, the U or D sticker first, then clockwise
+    facelets: [[usize; 3]; CORNERS],
+    moves: Vec<CornerMove>,
+    upper: PatternDatabase,
+    lower: PatternDatabase,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(build_tables)
+}
+
+fn build_tables() -> Tables {
+    let facelets = corner_facelets();
+    let mut moves = vec![];
+
+    for &face in TURNED_FACES.iter() {
+        let quarter = quarter_turn(&facelets, face);
+        let half = quarter.then(&quarter);
+        let inverse = half.then(&quarter);
+        moves.extend_from_slice(&[quarter, half, inverse]);
+    }
+
+    Tables {
+        upper: PatternDatabase::build(&UPPER_CORNERS, &moves),
+        lower: PatternDatabase::build(&LOWER_CORNERS, &moves),
+        facelets,
+        moves,
+    }
+}
+
+// position of the cubie and outward normal of the sticker
+fn sticker(index: usize) -> ([i8; 3], [i8; 3]) {
+    let row = (index % 4) / 2;
+    let col = (index % 2) as i8 * 2 - 1;
+    let down = if row == 0 { 1 } else { -1 };
+
+    match index / 4 {
+        0 => ([col, 1, -down], [0, 1, 0]),   // U
+        1 => ([1, down, -col], [1, 0, 0]),   // R
+        2 => ([col, down, 1], [0, 0, 1]),    // F
+        3 => ([col, -1, down], [0, -1, 0]),  // D
+        4 => ([-1, down, col], [-1, 0, 0]),  // L
+        _ => ([-col, down, -1], [0, 0, -1]), // B
+    }
+}
+
+fn sticker_index(position: [i8; 3], normal: [i8; 3]) -> usize {
+    (0..FACELETS)
+        .find(|&i| sticker(i) == (position, normal))
+        .expect("every corner sticker exists")
+}
+
+fn corner_facelets() -> [[usize; 3]; CORNERS] {
+    let mut facelets = [[0; 3]; CORNERS];
+
+    for (corner, &position) in CORNER_POSITIONS.iter().enumerate() {
+        let mut stickers: Vec<usize> = (0..FACELETS)
+            .filter(|&i| sticker(i).0 == position)
+            .collect();
+        // U or D sticker goes first
+        stickers.sort_by_key(|&i| sticker(i).1[1] == 0);
+
+        // clockwise seen from outside, if (n0 x n1) . position < 0
+        let (n0, n1) = (sticker(stickers[0]).1, sticker(stickers[1]).1);
+        let cross = [
+            n0[1] * n1[2] - n0[2] * n1[1],
+            n0[2] * n1[0] - n0[0] * n1[2],
+            n0[0] * n1[1] - n0[1] * n1[0],
+        ];
+        let dot: i8 = (0..3).map(|i| cross[i] * position[i]).sum();
+        if dot > 0 {
+            stickers.swap(1, 2);
+        }
+
+        facelets[corner] = [stickers[0], stickers[1], stickers[2]];
+    }
+
+    facelets
+}
+
+type Rotation = fn([i8; 3]) -> [i8; 3];
+
+// clockwise quarter turn of the face, as seen from outside the cube
+fn quarter_turn(facelets: &[[usize; 3]; CORNERS], face: char) -> CornerMove {
+    let (axis, rotate): (usize, Rotation) = match face {
+        'U' => (1, |[x, y, z]| [-z, y, x]),
+        'R' => (0, |[x, y, z]| [x, z, -y]),
+        _ => (2, |[x, y, z]| [y, -x, z]),
+    };
+    let mut corner_move = CornerMove {
+        target: [0, 1, 2, 3, 4, 5, 6, 7],
+        twist: [0; CORNERS],
+    };
+
+    for position in 0..CORNERS {
+        if CORNER_POSITIONS[position][axis] != 1 {
+            continue;
+        }
+
+        let (cubie, normal) = sticker(facelets[position][0]);
+        let moved = sticker_index(rotate(cubie), rotate(normal));
+        for (target, stickers) in facelets.iter().enumerate() {
+            if let Some(slot) = stickers.iter().position(|&s| s == moved) {
+                corner_move.target[position] = target;
+                corner_move.twist[position] = slot as u8;
+            }
+        }
+    }
+
+    corner_move
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::informed::astar_search_observed;
+    use crate::search::observers::CountingObserver;
+    use crate::search::uninformed::breadth_first_search_observed;
+
+    const SOLVED: &str = "UUUURRRRFFFFDDDDLLLLBBBB";
+
+    #[test]
+    fn test_solved_cube_facelets() {
+        let cube = PocketCube::from_facelets(SOLVED).unwrap();
+
+        assert!(cube.is_solved());
+        assert_eq!(SOLVED, PocketCube::solved().facelets());
+        assert_eq!(0, cube.h());
+    }
+
+    #[test]
+    fn test_quarter_turns_move_the_right_stickers() {
+        let cube = PocketCube::solved();
+
+        assert_eq!(
+            "UFUFRRRRFDFDDBDBLLLLUBUB",
+            cube.apply("R").unwrap().facelets()
+        );
+        assert_eq!(
+            "UUUUBBRRRRFFDDDDFFLLLLBB",
+            cube.apply("U").unwrap().facelets()
+        );
+        assert_eq!(
+            "UULLURURFFFFRRDDLDLDBBBB",
+            cube.apply("F").unwrap().facelets()
+        );
+    }
+
+    #[test]
+    fn test_turn_sequences_return_to_solved() {
+        let cube = PocketCube::solved();
+
+        assert!(cube.apply("R R R R").unwrap().is_solved());
+        assert!(cube.apply("U2 U2").unwrap().is_solved());
+        assert!(cube.apply("F F'").unwrap().is_solved());
+        assert!(cube.apply(&"R U R' U' ".repeat(6)).unwrap().is_solved());
+        assert!(!cube.apply(&"R U R' U' ".repeat(3)).unwrap().is_solved());
+    }
+
+    #[test]
+    fn test_facelets_round_trip_with_other_colors() {
+        let cube = PocketCube::solved().apply("R U F' R2 U'").unwrap();
+        let recolored: String = cube
+            .facelets()
+            .chars()
+            .map(|c| match c {
+                'U' => 'W',
+                'R' => 'R',
+                'F' => 'G',
+                'D' => 'Y',
+                'L' => 'O',
+                _ => 'B',
+            })
+            .collect();
+
+        assert_eq!(cube, PocketCube::from_facelets(&recolored).unwrap());
+    }
+
+    #[test]
+    fn test_from_facelets_rejects_invalid_cubes() {
+        assert_eq!(
+            Err("expected 24 facelets but got 23".to_string()),
+            PocketCube::from_facelets(&SOLVED[1..])
+        );
+        assert_eq!(
+            Err("color U appears 5 times, expected 4".to_string()),
+            PocketCube::from_facelets("UUUUURRRFFFFDDDDLLLLBBBB")
+        );
+        // URF corner twisted in place
+        assert_eq!(
+            Err("corners are twisted, the cube can not be solved".to_string()),
+            PocketCube::from_facelets("UUURFRRRFUFFDDDDLLLLBBBB")
+        );
+        // two stickers of URF corner swapped, which makes a mirrored corner
+        assert_eq!(
+            Err("corner 1 has impossible colors".to_string()),
+            PocketCube::from_facelets("UUUUFRRRFRFFDDDDLLLLBBBB")
+        );
+    }
+
+    #[test]
+    fn test_apply_rejects_unknown_turns() {
+        assert!(PocketCube::solved().apply("R D").is_err());
+        assert!(PocketCube::solved().apply("R3").is_err());
+    }
+
+    #[test]
+    fn test_astar_with_pattern_database_finds_optimal_solution() {
+        let cube = PocketCube::solved().apply("R U2 F' U R'").unwrap();
+        let mut astar_counter = CountingObserver::new();
+        let mut bfs_counter = CountingObserver::new();
+
+        let astar = astar_search_observed(Box::new(cube.clone()), &mut astar_counter).unwrap();
+        let bfs = breadth_first_search_observed(Box::new(cube.clone()), &mut bfs_counter).unwrap();
+
+        assert_eq!(bfs.depth(), astar.depth());
+        assert!(astar.depth() <= 5);
+        assert!(cube.h() as usize <= astar.depth());
+        assert!(astar_counter.expanded * 10 < bfs_counter.expanded);
+    }
+
+    #[test]
+    fn test_astar_solves_long_scramble() {
+        let cube = PocketCube::solved()
+            .apply("R U R' F2 U' R F R2 U F' U2")
+            .unwrap();
+        let solution =
+            astar_search_observed(Box::new(cube.clone()), &mut CountingObserver::new()).unwrap();
+        let turns: Vec<String> = solution.solution()[1..]
+            .iter()
+            .map(|action| cube.action_name(action))
+            .collect();
+
+        assert!(solution.depth() <= 11);
+        assert!(cube.apply(&turns.join(" ")).unwrap().is_solved());
+    }
+}
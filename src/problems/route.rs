@@ -0,0 +1,351 @@
+//! Route finding on a weighted graph of cities
+//! The map is loaded from a CSV edge list `from,to,cost`, roads go both ways.
+//! Straight line distances to one goal city can be added as a heuristic,
+//! other goals fall back to zero, which is still admissible.
+//! source:
+//! AIMA 3rd edition, chapter 3.1 and 3.5.2; Romania map from
+//! https://github.com/aimacode/aima-python/blob/master/search.py
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use crate::search::{Action, SearchProblem};
+
+const ROMANIA_ROADS: &str = include_str!("romania.csv");
+const ROMANIA_STRAIGHT_LINE_DISTANCES: &str = include_str!("romania_sld.csv");
+pub const ROMANIA_SLD_GOAL: &str = "Bucharest";
+
+#[derive(Debug, Clone, Default)]
+pub struct RouteMap {
+    cities: Vec<String>,
+    indices: HashMap<String, usize>,
+    // neighbors of every city with the cost of the road
+    roads: Vec<Vec<(usize, u32)>>,
+    // straight line distances to the heuristic goal city
+    heuristic_goal: Option<usize>,
+    distances: Vec<u32>,
+}
+
+impl RouteMap {
+    /// parses edge list with lines `from,to,cost`; blank lines and lines starting with # are skipped
+    pub fn from_csv(text: &str) -> Result<Self, String> {
+        let mut map = RouteMap::default();
+
+        for (line_no, fields) in csv_records(text) {
+            if fields.len() != 3 {
+                return Err(format!(
+                    "line {}: expected from,to,cost but got {} fields",
+                    line_no,
+                    fields.len()
+                ));
+            }
+            let cost = parse_number(fields[2], line_no)?;
+            let from = map.add_city(fields[0]);
+            let to = map.add_city(fields[1]);
+
+            map.roads[from].push((to, cost));
+            map.roads[to].push((from, cost));
+        }
+
+        Ok(map)
+    }
+
+    /// the AIMA map of Romania with straight line distances to Bucharest
+    pub fn romania() -> Self {
+        RouteMap::from_csv(ROMANIA_ROADS)
+            .and_then(|map| {
+                map.with_straight_line_distances(ROMANIA_SLD_GOAL, ROMANIA_STRAIGHT_LINE_DISTANCES)
+            })
+            .expect("bundled Romania map is valid")
+    }
+
+    /// adds heuristic from lines `city,distance` with the distances to the `goal` city
+    pub fn with_straight_line_distances(mut self, goal: &str, text: &str) -> Result<Self, String> {
+        let goal = self.city_index(goal)?;
+        let mut distances = vec![None; self.cities.len()];
+
+        for (line_no, fields) in csv_records(text) {
+            if fields.len() != 2 {
+                return Err(format!(
+                    "line {}: expected city,distance but got {} fields",
+                    line_no,
+                    fields.len()
+                ));
+            }
+            let city = self
+                .city_index(fields[0])
+                .map_err(|err| format!("line {}: {}", line_no, err))?;
+            distances[city] = Some(parse_number(fields[1], line_no)?);
+        }
+
+        if let Some(missing) = distances.iter().position(|d| d.is_none()) {
+            return Err(format!("missing distance for {}", self.cities[missing]));
+        }
+
+        self.heuristic_goal = Some(goal);
+        self.distances = distances.into_iter().flatten().collect();
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cities.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cities.is_empty()
+    }
+
+    pub fn city_name(&self, index: usize) -> &str {
+        &self.cities[index]
+    }
+
+    pub fn city_index(&self, name: &str) -> Result<usize, String> {
+        self.indices
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("unknown city {}", name))
+    }
+
+    pub fn neighbors(&self, city: usize) -> &[(usize, u32)] {
+        &self.roads[city]
+    }
+
+    /// returns cost of the direct road between two cities
+    pub fn road_cost(&self, from: usize, to: usize) -> Option<u32> {
+        self.roads[from]
+            .iter()
+            .find(|&&(city, _)| city == to)
+            .map(|&(_, cost)| cost)
+    }
+
+    /// straight line distance from the city to the goal, 0 if it isn't known
+    pub fn distance(&self, city: usize, goal: usize) -> u32 {
+        match self.heuristic_goal {
+            Some(heuristic_goal) if heuristic_goal == goal => self.distances[city],
+            _ => 0,
+        }
+    }
+
+    fn add_city(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
+        }
+
+        let index = self.cities.len();
+        self.cities.push(name.to_string());
+        self.indices.insert(name.to_string(), index);
+        self.roads.push(vec![]);
+        index
+    }
+}
+
+fn csv_records(text: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_no, line)| (line_no, line.split(',').map(|f| f.trim()).collect()))
+}
+
+fn parse_number(field: &str, line_no: usize) -> Result<u32, String> {
+    field
+        .parse::<u32>()
+        .map_err(|_| format!("line {}: {} is not a number", line_no, field))
+}
+
+/// Finds a route between two cities; actions are indices of the neighboring cities
+#[derive(Debug, Clone)]
+pub struct RouteProblem {
+    map: Rc<RouteMap>,
+    city: usize,
+    goal: usize,
+    // cost of the road that led to this city
+    step_cost: u32,
+}
+
+impl RouteProblem {
+    pub fn new(map: Rc<RouteMap>, from: &str, to: &str) -> Result<Self, String> {
+        let city = map.city_index(from)?;
+        let goal = map.city_index(to)?;
+
+        Ok(RouteProblem {
+            map,
+            city,
+            goal,
+            step_cost: 0,
+        })
+    }
+
+    pub fn city(&self) -> &str {
+        self.map.city_name(self.city)
+    }
+
+    pub fn goal(&self) -> &str {
+        self.map.city_name(self.goal)
+    }
+}
+
+impl SearchProblem for RouteProblem {
+    fn actions(&self) -> Vec<Action> {
+        self.map
+            .neighbors(self.city)
+            .iter()
+            .map(|&(city, _)| Action(city))
+            .collect()
+    }
+
+    fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
+        let step_cost = self
+            .map
+            .road_cost(self.city, action.0)
+            .expect("action must lead to a neighboring city");
+
+        Box::new(RouteProblem {
+            map: self.map.clone(),
+            city: action.0,
+            goal: self.goal,
+            step_cost,
+        })
+    }
+
+    fn test_goal(&self) -> bool {
+        self.city == self.goal
+    }
+
+    fn path_cost(&self) -> u32 {
+        self.step_cost
+    }
+
+    fn value(&self) -> u32 {
+        self.map.distance(self.city, self.goal)
+    }
+
+    fn as_string(&self) -> String {
+        format!("Route(city: {})", self.city())
+    }
+
+    fn hash_code(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.city.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        self.map.city_name(action.0).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::informed::astar_search_observed;
+    use crate::search::observers::CountingObserver;
+    use crate::search::uninformed::{breadth_first_search, uniform_cost_search_observed};
+    use crate::search::SearchNode;
+
+    fn romania(from: &str, to: &str) -> Box<dyn SearchProblem> {
+        let map = Rc::new(RouteMap::romania());
+        Box::new(RouteProblem::new(map, from, to).unwrap())
+    }
+
+    fn route(node: &SearchNode) -> Vec<String> {
+        let mut cities: Vec<String> = node.path().iter().map(|n| n.item().as_string()).collect();
+        cities.reverse();
+        cities
+    }
+
+    fn cities(names: &[&str]) -> Vec<String> {
+        names
+            .iter()
+            .map(|name| format!("Route(city: {})", name))
+            .collect()
+    }
+
+    #[test]
+    fn test_romania_map_has_all_cities() {
+        let map = RouteMap::romania();
+
+        assert_eq!(20, map.len());
+        assert_eq!(4, map.neighbors(map.city_index("Bucharest").unwrap()).len());
+        assert_eq!(
+            Some(140),
+            map.road_cost(
+                map.city_index("Sibiu").unwrap(),
+                map.city_index("Arad").unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn test_breadth_first_finds_fewest_roads_not_shortest_route() {
+        let solution = breadth_first_search(romania("Arad", "Bucharest")).unwrap();
+
+        assert_eq!(450, solution.path_cost());
+        assert_eq!(
+            cities(&["Arad", "Sibiu", "Fagaras", "Bucharest"]),
+            route(&solution)
+        );
+    }
+
+    #[test]
+    fn test_uniform_cost_and_astar_find_shortest_route() {
+        let expected = cities(&["Arad", "Sibiu", "Rimnicu Vilcea", "Pitesti", "Bucharest"]);
+        let mut ucs_counter = CountingObserver::new();
+        let mut astar_counter = CountingObserver::new();
+
+        let ucs = uniform_cost_search_observed(romania("Arad", "Bucharest"), &mut ucs_counter);
+        let astar = astar_search_observed(romania("Arad", "Bucharest"), &mut astar_counter);
+
+        for solution in [ucs.unwrap(), astar.unwrap()].iter() {
+            assert_eq!(418, solution.path_cost());
+            assert_eq!(expected, route(solution));
+        }
+        assert!(astar_counter.expanded < ucs_counter.expanded);
+    }
+
+    #[test]
+    fn test_heuristic_is_zero_for_other_goals() {
+        let map = Rc::new(RouteMap::romania());
+        let to_bucharest = RouteProblem::new(map.clone(), "Arad", "Bucharest").unwrap();
+        let to_craiova = RouteProblem::new(map, "Arad", "Craiova").unwrap();
+
+        assert_eq!(366, to_bucharest.value());
+        assert_eq!(0, to_craiova.value());
+    }
+
+    #[test]
+    fn test_action_name_is_city_name() {
+        let problem = romania("Arad", "Bucharest");
+        let names: Vec<String> = problem
+            .actions()
+            .iter()
+            .map(|action| problem.action_name(action))
+            .collect();
+
+        assert_eq!(vec!["Zerind", "Sibiu", "Timisoara"], names);
+    }
+
+    #[test]
+    fn test_route_map_from_csv_reports_bad_lines() {
+        let err = RouteMap::from_csv("# roads\nA,B,10\n\nB,C,far\n").unwrap_err();
+        assert_eq!("line 4: far is not a number", err);
+
+        let err = RouteMap::from_csv("A,B\n").unwrap_err();
+        assert_eq!("line 1: expected from,to,cost but got 2 fields", err);
+
+        let map = RouteMap::from_csv("A,B,10\n").unwrap();
+        let err = map.with_straight_line_distances("A", "A,0\n").unwrap_err();
+        assert_eq!("missing distance for B", err);
+    }
+
+    #[test]
+    fn test_route_problem_rejects_unknown_city() {
+        let map = Rc::new(RouteMap::romania());
+        let err = RouteProblem::new(map, "Arad", "Budapest").unwrap_err();
+
+        assert_eq!("unknown city Budapest", err);
+    }
+}
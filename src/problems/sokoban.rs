@@ -0,0 +1,560 @@
+//! Sokoban
+//! The player walks around the warehouse and pushes boxes onto goal squares;
+//! walking is free, so the actions are pushes and the path cost is the number of pushes.
+//! Pushes can not be undone, which makes many states dead ends - pushes that cause
+//! a simple deadlock are never generated:
+//! * dead squares - corners and walls from which no box can reach any goal
+//! * frozen boxes - a box that can move neither horizontally nor vertically, off a goal
+//!
+//! Levels are read in the XSB format:
+//! `#` wall, `@` player, `+` player on goal, `$` box, `*` box on goal, `.` goal,
+//! space, `-` or `_` floor; lines starting with `;` are comments.
+//! source:
+//! http://sokobano.de/wiki/index.php?title=Level_format
+//! http://sokobano.de/wiki/index.php?title=How_to_detect_deadlocks
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use crate::search::{Action, SearchProblem};
+
+// exact matching is exponential in the number of boxes, bigger levels use a weaker bound
+const MAX_MATCHED_BOXES: usize = 16;
+const UNREACHABLE: u32 = u32::MAX;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
+
+/// the static part of a level, shared by all states
+#[derive(Debug)]
+struct Level {
+    width: usize,
+    height: usize,
+    walls: Vec<bool>,
+    goals: Vec<usize>,
+    // fewest pushes from every square to every goal, ignoring other boxes
+    goal_distances: Vec<Vec<u32>>,
+}
+
+impl Level {
+    fn is_wall(&self, square: usize) -> bool {
+        self.walls[square]
+    }
+
+    fn is_goal(&self, square: usize) -> bool {
+        self.goals.contains(&square)
+    }
+
+    fn is_dead(&self, square: usize) -> bool {
+        self.goal_distances
+            .iter()
+            .all(|distances| distances[square] == UNREACHABLE)
+    }
+
+    /// next square in the direction, walls are around the whole level
+    fn neighbor(&self, square: usize, direction: Direction) -> Option<usize> {
+        let (col, row) = (square % self.width, square / self.width);
+        let next = match direction {
+            Direction::Up if row > 0 => square - self.width,
+            Direction::Down if row + 1 < self.height => square + self.width,
+            Direction::Left if col > 0 => square - 1,
+            Direction::Right if col + 1 < self.width => square + 1,
+            _ => return None,
+        };
+
+        Some(next).filter(|&next| !self.is_wall(next))
+    }
+
+    // box can be pulled from the goal backwards, if there is room for the player behind it
+    fn pull_distances(&self, goal: usize) -> Vec<u32> {
+        let mut distances = vec![UNREACHABLE; self.walls.len()];
+        let mut queue = VecDeque::new();
+
+        distances[goal] = 0;
+        queue.push_back(goal);
+
+        while let Some(square) = queue.pop_front() {
+            for &direction in DIRECTIONS.iter() {
+                let pulled = self.neighbor(square, direction);
+                let player = pulled.and_then(|p| self.neighbor(p, direction));
+
+                if let (Some(pulled), Some(_)) = (pulled, player) {
+                    if distances[pulled] == UNREACHABLE {
+                        distances[pulled] = distances[square] + 1;
+                        queue.push_back(pulled);
+                    }
+                }
+            }
+        }
+
+        distances
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Sokoban {
+    level: Rc<Level>,
+    player: usize,
+    // sorted, so the same boxes always make the same state
+    boxes: Vec<usize>,
+}
+
+impl Sokoban {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let rows: Vec<(usize, &str)> = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim_end()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with(';'))
+            .collect();
+        let width = rows.iter().map(|(_, line)| line.len()).max().unwrap_or(0);
+        let height = rows.len();
+
+        let mut walls = vec![false; width * height];
+        let mut goals = vec![];
+        let mut boxes = vec![];
+        let mut players = vec![];
+
+        for (row, (line_no, line)) in rows.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                let square = row * width + col;
+                match c {
+                    '#' => walls[square] = true,
+                    ' ' | '-' | '_' => {}
+                    '.' => goals.push(square),
+                    '$' => boxes.push(square),
+                    '*' => {
+                        boxes.push(square);
+                        goals.push(square);
+                    }
+                    '@' => players.push(square),
+                    '+' => {
+                        players.push(square);
+                        goals.push(square);
+                    }
+                    _ => return Err(format!("line {}: unknown character {:?}", line_no, c)),
+                }
+            }
+        }
+
+        if players.len() != 1 {
+            return Err(format!("expected one player but got {}", players.len()));
+        }
+        if boxes.is_empty() || boxes.len() != goals.len() {
+            return Err(format!(
+                "expected the same number of boxes and goals but got {} and {}",
+                boxes.len(),
+                goals.len()
+            ));
+        }
+
+        let mut level = Level {
+            width,
+            height,
+            walls,
+            goals,
+            goal_distances: vec![],
+        };
+        level.goal_distances = level
+            .goals
+            .iter()
+            .map(|&goal| level.pull_distances(goal))
+            .collect();
+        boxes.sort_unstable();
+
+        Ok(Sokoban {
+            level: Rc::new(level),
+            player: players[0],
+            boxes,
+        })
+    }
+
+    /// the level in XSB format
+    pub fn to_xsb(&self) -> String {
+        let level = &self.level;
+        let mut rows = vec![];
+
+        for row in 0..level.height {
+            let line: String = (0..level.width)
+                .map(|col| {
+                    let square = row * level.width + col;
+                    match (
+                        level.is_wall(square),
+                        self.has_box(square),
+                        square == self.player,
+                        level.is_goal(square),
+                    ) {
+                        (true, _, _, _) => '#',
+                        (_, true, _, true) => '*',
+                        (_, true, _, false) => '$',
+                        (_, _, true, true) => '+',
+                        (_, _, true, false) => '@',
+                        (_, _, _, true) => '.',
+                        _ => ' ',
+                    }
+                })
+                .collect();
+            rows.push(line.trim_end().to_string());
+        }
+
+        rows.join("\n")
+    }
+
+    pub fn boxes(&self) -> &[usize] {
+        &self.boxes
+    }
+
+    /// column and row of the square
+    pub fn coordinates(&self, square: usize) -> (usize, usize) {
+        (square % self.level.width, square / self.level.width)
+    }
+
+    /// true if any box stands on a square it can never leave towards a goal
+    pub fn is_deadlocked(&self) -> bool {
+        self.boxes.iter().any(|&square| {
+            self.level.is_dead(square) || self.is_frozen_off_goal(square, &self.boxes)
+        })
+    }
+
+    fn has_box(&self, square: usize) -> bool {
+        self.boxes.binary_search(&square).is_ok()
+    }
+
+    fn is_free(&self, square: usize) -> bool {
+        !self.level.is_wall(square) && !self.has_box(square)
+    }
+
+    /// squares the player can walk to without pushing anything
+    fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.level.walls.len()];
+        let mut queue = VecDeque::new();
+
+        reachable[self.player] = true;
+        queue.push_back(self.player);
+
+        while let Some(square) = queue.pop_front() {
+            for &direction in DIRECTIONS.iter() {
+                if let Some(next) = self.level.neighbor(square, direction) {
+                    if !reachable[next] && !self.has_box(next) {
+                        reachable[next] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        reachable
+    }
+
+    // actions are numbered as box square * 4 + direction of the push
+    fn push_target(&self, action: &Action) -> (usize, usize, Direction) {
+        let square = action.0 / DIRECTIONS.len();
+        let direction = DIRECTIONS[action.0 % DIRECTIONS.len()];
+        let target = self
+            .level
+            .neighbor(square, direction)
+            .expect("box must be pushed to a floor");
+
+        (square, target, direction)
+    }
+
+    fn is_frozen_off_goal(&self, square: usize, boxes: &[usize]) -> bool {
+        let mut frozen = vec![];
+
+        self.is_frozen(square, boxes, &mut vec![square], &mut frozen)
+            && frozen.iter().any(|&square| !self.level.is_goal(square))
+    }
+
+    /// box is frozen, if it is blocked on both axes by walls, dead squares or other frozen boxes;
+    /// boxes on the `path` are treated as walls, so the check does not go in circles
+    fn is_frozen(
+        &self,
+        square: usize,
+        boxes: &[usize],
+        path: &mut Vec<usize>,
+        frozen: &mut Vec<usize>,
+    ) -> bool {
+        let axes = [
+            (Direction::Left, Direction::Right),
+            (Direction::Up, Direction::Down),
+        ];
+
+        for &(first, second) in axes.iter() {
+            let sides = [
+                self.level.neighbor(square, first),
+                self.level.neighbor(square, second),
+            ];
+            let wall = sides.iter().any(|side| side.is_none());
+            let dead = sides
+                .iter()
+                .all(|side| side.is_none_or(|s| self.level.is_dead(s)));
+            let blocked_by_box = sides.iter().flatten().any(|&side| {
+                if boxes.binary_search(&side).is_err() {
+                    return false;
+                }
+                if path.contains(&side) {
+                    return true;
+                }
+                path.push(side);
+                let blocked = self.is_frozen(side, boxes, path, frozen);
+                path.pop();
+                blocked
+            });
+
+            if !(wall || dead || blocked_by_box) {
+                return false;
+            }
+        }
+
+        frozen.push(square);
+        true
+    }
+
+    /// minimal total distance of boxes to goals, every goal taking just one box;
+    /// u32::MAX if the boxes can't be matched with goals at all
+    fn matching_distance(&self) -> u32 {
+        let costs: Vec<Vec<u32>> = self
+            .boxes
+            .iter()
+            .map(|&square| {
+                self.level
+                    .goal_distances
+                    .iter()
+                    .map(|distances| distances[square])
+                    .collect()
+            })
+            .collect();
+
+        if costs.len() > MAX_MATCHED_BOXES {
+            return costs
+                .iter()
+                .map(|row| *row.iter().min().unwrap())
+                .fold(0u32, |sum, cost| sum.saturating_add(cost));
+        }
+
+        // best[mask] is the cost of placing the first |mask| boxes onto the goals in the mask
+        let goals = self.level.goals.len();
+        let mut best = vec![UNREACHABLE; 1 << goals];
+        best[0] = 0;
+
+        for mask in 0..(1usize << goals) {
+            let placed = mask.count_ones() as usize;
+            if best[mask] == UNREACHABLE || placed == costs.len() {
+                continue;
+            }
+            for (goal, &cost) in costs[placed].iter().enumerate() {
+                if mask & (1 << goal) == 0 && cost != UNREACHABLE {
+                    let next = mask | (1 << goal);
+                    best[next] = best[next].min(best[mask] + cost);
+                }
+            }
+        }
+
+        best[(1 << goals) - 1]
+    }
+}
+
+impl SearchProblem for Sokoban {
+    fn actions(&self) -> Vec<Action> {
+        let reachable = self.reachable();
+        let mut actions = vec![];
+
+        for &square in self.boxes.iter() {
+            for (i, &direction) in DIRECTIONS.iter().enumerate() {
+                let behind = match direction {
+                    Direction::Up => self.level.neighbor(square, Direction::Down),
+                    Direction::Down => self.level.neighbor(square, Direction::Up),
+                    Direction::Left => self.level.neighbor(square, Direction::Right),
+                    Direction::Right => self.level.neighbor(square, Direction::Left),
+                };
+                let target = match self.level.neighbor(square, direction) {
+                    Some(target) if self.is_free(target) && !self.level.is_dead(target) => target,
+                    _ => continue,
+                };
+
+                if behind.is_some_and(|b| reachable[b]) {
+                    let mut boxes: Vec<usize> = self.boxes.clone();
+                    boxes.retain(|&b| b != square);
+                    boxes.push(target);
+                    boxes.sort_unstable();
+
+                    if !self.is_frozen_off_goal(target, &boxes) {
+                        actions.push(Action(square * DIRECTIONS.len() + i));
+                    }
+                }
+            }
+        }
+
+        actions
+    }
+
+    fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
+        let (square, target, _) = self.push_target(action);
+        let mut boxes = self.boxes.clone();
+
+        boxes.retain(|&b| b != square);
+        boxes.push(target);
+        boxes.sort_unstable();
+
+        Box::new(Sokoban {
+            level: self.level.clone(),
+            player: square,
+            boxes,
+        })
+    }
+
+    fn test_goal(&self) -> bool {
+        self.boxes.iter().all(|&square| self.level.is_goal(square))
+    }
+
+    // every push costs the same, walking is free
+    fn path_cost(&self) -> u32 {
+        1
+    }
+
+    fn value(&self) -> u32 {
+        self.matching_distance()
+    }
+
+    fn as_string(&self) -> String {
+        format!("Sokoban(\n{}\n)", self.to_xsb())
+    }
+
+    // player positions within the same reachable area are the same state
+    fn hash_code(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        let area = self.reachable().iter().position(|&r| r).unwrap();
+
+        area.hash(&mut hasher);
+        self.boxes.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        let (square, _, direction) = self.push_target(action);
+        let (col, row) = self.coordinates(square);
+
+        format!("{:?} ({}, {})", direction, col, row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::informed::astar_search_observed;
+    use crate::search::observers::CountingObserver;
+    use crate::search::uninformed::{breadth_first_search, breadth_first_search_observed};
+
+    // Microban level 1 by David W. Skinner
+    const MICROBAN_1: &str = "\
+####
+# .#
+#  ###
+#*@  #
+#  $ #
+#  ###
+####";
+
+    fn action_names(problem: &Sokoban) -> Vec<String> {
+        problem
+            .actions()
+            .iter()
+            .map(|action| problem.action_name(action))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let level = Sokoban::parse(&format!("; Microban 1\n{}\n\n", MICROBAN_1)).unwrap();
+
+        assert_eq!(MICROBAN_1, level.to_xsb());
+        assert_eq!(2, level.boxes().len());
+        assert!(!level.test_goal());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_levels() {
+        assert_eq!(
+            "line 2: unknown character 'x'",
+            Sokoban::parse("#####\n#@$x#\n#####").unwrap_err()
+        );
+        assert_eq!(
+            "expected one player but got 2",
+            Sokoban::parse("#####\n#@$.@#\n#####").unwrap_err()
+        );
+        assert_eq!(
+            "expected the same number of boxes and goals but got 2 and 1",
+            Sokoban::parse("######\n#@$$.#\n######").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_single_push_solves_corridor() {
+        let level = Sokoban::parse("#####\n#@$.#\n#####").unwrap();
+        let solution = breadth_first_search(Box::new(level.clone())).unwrap();
+
+        assert_eq!(vec!["Right (2, 1)"], action_names(&level));
+        assert_eq!(1, solution.path_cost());
+    }
+
+    #[test]
+    fn test_pushes_onto_dead_squares_are_not_generated() {
+        let level = Sokoban::parse("######\n#    #\n#@$ .#\n#    #\n######").unwrap();
+
+        // boxes can not leave the walls without goals, corners included
+        assert_eq!(vec!["Right (2, 2)"], action_names(&level));
+    }
+
+    #[test]
+    fn test_push_that_freezes_two_boxes_is_not_generated() {
+        let level = Sokoban::parse("######\n#. $.#\n# $  #\n# @  #\n######").unwrap();
+        let names = action_names(&level);
+
+        // both boxes would stand against the wall next to each other, off the goals
+        assert!(!names.contains(&"Up (2, 2)".to_string()));
+        assert!(names.contains(&"Left (3, 1)".to_string()));
+    }
+
+    #[test]
+    fn test_level_with_dead_box_has_no_solution() {
+        let level = Sokoban::parse("#####\n#$@ #\n#.  #\n#####").unwrap();
+
+        assert!(level.is_deadlocked());
+        assert!(breadth_first_search(Box::new(level)).is_none());
+    }
+
+    #[test]
+    fn test_matching_distance_assigns_every_goal_once() {
+        let level = Sokoban::parse("########\n#      #\n#.$$  .#\n#  @   #\n########").unwrap();
+
+        // the left goal is the nearest one for both boxes, but only one box can take it
+        assert_eq!(1 + 3, level.value());
+    }
+
+    #[test]
+    fn test_astar_and_breadth_first_find_same_number_of_pushes() {
+        let level = Sokoban::parse(MICROBAN_1).unwrap();
+        let mut astar_counter = CountingObserver::new();
+        let mut bfs_counter = CountingObserver::new();
+
+        let astar = astar_search_observed(Box::new(level.clone()), &mut astar_counter).unwrap();
+        let bfs = breadth_first_search_observed(Box::new(level), &mut bfs_counter).unwrap();
+
+        assert_eq!(bfs.path_cost(), astar.path_cost());
+        assert!(astar_counter.expanded <= bfs_counter.expanded);
+    }
+}
@@ -0,0 +1,279 @@
+//! Vacuum world
+//! The agent moves along a row of squares and sucks up the dirt;
+//! moving against the wall does nothing, just like sucking a clean square.
+//! In the erratic vacuum world sucking a dirty square sometimes cleans the neighbors too,
+//! and sucking a clean square sometimes drops dirt on it.
+//! source:
+//! AIMA 3rd edition, chapter 2.1, 3.2.1 and 4.3.1
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::search::nondeterministic::NondeterministicProblem;
+use crate::search::{Action, SearchProblem};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum VacuumAction {
+    Left,
+    Right,
+    Suck,
+}
+
+impl From<VacuumAction> for Action {
+    fn from(vacuum_action: VacuumAction) -> Self {
+        Action(vacuum_action as usize)
+    }
+}
+
+impl From<&Action> for VacuumAction {
+    fn from(action: &Action) -> Self {
+        match action.0 {
+            0 => VacuumAction::Left,
+            1 => VacuumAction::Right,
+            _ => VacuumAction::Suck,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VacuumWorld {
+    position: usize,
+    dirt: Vec<bool>,
+}
+
+impl VacuumWorld {
+    pub fn new(position: usize, dirt: Vec<bool>) -> Self {
+        assert!(position < dirt.len(), "agent must be inside the world");
+
+        VacuumWorld { position, dirt }
+    }
+
+    /// the two square world of the book, both squares dirty and the agent on the left
+    pub fn two_squares() -> Self {
+        VacuumWorld::new(0, vec![true, true])
+    }
+
+    /// every position of the agent with every distribution of dirt
+    pub fn all_states(squares: usize) -> Vec<VacuumWorld> {
+        let mut states = vec![];
+
+        for position in 0..squares {
+            for mask in 0..1usize << squares {
+                let dirt = (0..squares).map(|square| mask >> square & 1 == 1).collect();
+                states.push(VacuumWorld::new(position, dirt));
+            }
+        }
+
+        states
+    }
+}
+
+impl SearchProblem for VacuumWorld {
+    // every action is always possible, even if it changes nothing
+    fn actions(&self) -> Vec<Action> {
+        vec![
+            VacuumAction::Left.into(),
+            VacuumAction::Right.into(),
+            VacuumAction::Suck.into(),
+        ]
+    }
+
+    fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
+        let mut next = self.clone();
+
+        match VacuumAction::from(action) {
+            VacuumAction::Left => next.position = self.position.saturating_sub(1),
+            VacuumAction::Right => next.position = (self.position + 1).min(self.dirt.len() - 1),
+            VacuumAction::Suck => next.dirt[self.position] = false,
+        }
+
+        Box::new(next)
+    }
+
+    fn test_goal(&self) -> bool {
+        self.dirt.iter().all(|&dirty| !dirty)
+    }
+
+    fn path_cost(&self) -> u32 {
+        1
+    }
+
+    // dirty squares left
+    fn value(&self) -> u32 {
+        self.dirt.iter().filter(|&&dirty| dirty).count() as u32
+    }
+
+    fn as_string(&self) -> String {
+        let squares: String = self
+            .dirt
+            .iter()
+            .enumerate()
+            .map(|(i, &dirty)| match (i == self.position, dirty) {
+                (true, true) => 'A',
+                (true, false) => 'a',
+                (false, true) => '*',
+                (false, false) => '.',
+            })
+            .collect();
+
+        format!("VacuumWorld({})", squares)
+    }
+
+    fn hash_code(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        format!("{:?}", VacuumAction::from(action))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErraticVacuumWorld(VacuumWorld);
+
+impl ErraticVacuumWorld {
+    pub fn new(position: usize, dirt: Vec<bool>) -> Self {
+        ErraticVacuumWorld(VacuumWorld::new(position, dirt))
+    }
+
+    /// state 1 of the book, both squares dirty and the agent on the left
+    pub fn two_squares() -> Self {
+        ErraticVacuumWorld(VacuumWorld::two_squares())
+    }
+}
+
+impl NondeterministicProblem for ErraticVacuumWorld {
+    // sucking first, in the order of the book
+    fn actions(&self) -> Vec<Action> {
+        vec![
+            VacuumAction::Suck.into(),
+            VacuumAction::Left.into(),
+            VacuumAction::Right.into(),
+        ]
+    }
+
+    fn results(&self, action: &Action) -> Vec<Box<dyn NondeterministicProblem>> {
+        let world = &self.0;
+        let mut outcomes = vec![];
+
+        match VacuumAction::from(action) {
+            VacuumAction::Suck if world.dirt[world.position] => {
+                let mut cleaned = world.clone();
+                cleaned.dirt[world.position] = false;
+                outcomes.push(cleaned.clone());
+
+                let neighbors = world.position.saturating_sub(1)..=world.position + 1;
+                for square in neighbors.filter(|&square| square < world.dirt.len()) {
+                    cleaned.dirt[square] = false;
+                }
+                outcomes.push(cleaned);
+            }
+            VacuumAction::Suck => {
+                let mut dirtied = world.clone();
+                dirtied.dirt[world.position] = true;
+                outcomes.push(world.clone());
+                outcomes.push(dirtied);
+            }
+            VacuumAction::Left | VacuumAction::Right => {
+                let mut moved = world.clone();
+                moved.position = match VacuumAction::from(action) {
+                    VacuumAction::Left => world.position.saturating_sub(1),
+                    _ => (world.position + 1).min(world.dirt.len() - 1),
+                };
+                outcomes.push(moved);
+            }
+        }
+        outcomes.dedup();
+
+        outcomes
+            .into_iter()
+            .map(|world| Box::new(ErraticVacuumWorld(world)) as Box<dyn NondeterministicProblem>)
+            .collect()
+    }
+
+    fn test_goal(&self) -> bool {
+        self.0.test_goal()
+    }
+
+    fn as_string(&self) -> String {
+        self.0.as_string()
+    }
+
+    fn hash_code(&self) -> u64 {
+        self.0.hash_code()
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        self.0.action_name(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::nondeterministic::{and_or_graph_search, ConditionalPlan};
+    use crate::search::uninformed::{breadth_first_search, uniform_cost_search};
+
+    #[test]
+    fn test_two_squares_are_cleaned_in_three_steps() {
+        let problem = VacuumWorld::two_squares();
+        let solution = breadth_first_search(Box::new(problem.clone())).unwrap();
+        let names: Vec<String> = solution.solution()[1..]
+            .iter()
+            .map(|action| problem.action_name(action))
+            .collect();
+
+        assert_eq!(vec!["Suck", "Right", "Suck"], names);
+    }
+
+    #[test]
+    fn test_dirt_at_both_ends_of_a_long_row() {
+        let mut dirt = vec![false; 6];
+        dirt[0] = true;
+        dirt[5] = true;
+        let solution = uniform_cost_search(Box::new(VacuumWorld::new(2, dirt))).unwrap();
+
+        // two moves to the left end and five to the right end, plus two sucks
+        assert_eq!(9, solution.path_cost());
+    }
+
+    #[test]
+    fn test_moving_against_the_wall_changes_nothing() {
+        let problem = VacuumWorld::new(0, vec![false, true]);
+        let moved = problem.result(&VacuumAction::Left.into());
+
+        assert_eq!(problem.hash_code(), moved.hash_code());
+        assert_eq!("VacuumWorld(a*)", moved.as_string());
+    }
+
+    #[test]
+    fn test_erratic_suck_may_clean_the_neighbor() {
+        let problem = ErraticVacuumWorld::two_squares();
+        let outcomes: Vec<String> = problem
+            .results(&VacuumAction::Suck.into())
+            .iter()
+            .map(|state| state.as_string())
+            .collect();
+
+        assert_eq!(vec!["VacuumWorld(a*)", "VacuumWorld(a.)"], outcomes);
+    }
+
+    #[test]
+    fn test_erratic_vacuum_plan_from_the_book() {
+        let plan = and_or_graph_search(Box::new(ErraticVacuumWorld::two_squares())).unwrap();
+
+        // [Suck, if State = 5 then [Right, Suck] else []]
+        assert_eq!(3, plan.depth());
+        assert_eq!(
+            Some(&ConditionalPlan::Done),
+            plan.branch(&ErraticVacuumWorld::new(0, vec![false, false]))
+        );
+        assert_eq!(
+            "Suck\nif VacuumWorld(a*):\n    Right\n    Suck\nif VacuumWorld(a.):\n    goal\n",
+            plan.to_string()
+        );
+    }
+}
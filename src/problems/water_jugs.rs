@@ -0,0 +1,167 @@
+//! Water jugs
+//! Jugs without any markings can be filled from the tap, emptied, or poured into another jug
+//! until it is full; the goal is to measure exact amount of water in any of the jugs.
+//! source:
+//! AIMA 3rd edition, exercise 3.15
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use crate::search::{Action, SearchProblem};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum JugAction {
+    Fill(usize),
+    Empty(usize),
+    Pour(usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WaterJugs {
+    capacities: Vec<u32>,
+    levels: Vec<u32>,
+    goal: u32,
+}
+
+impl WaterJugs {
+    /// starts with all jugs empty
+    pub fn new(capacities: Vec<u32>, goal: u32) -> Self {
+        let levels = vec![0; capacities.len()];
+
+        WaterJugs {
+            capacities,
+            levels,
+            goal,
+        }
+    }
+
+    pub fn levels(&self) -> &[u32] {
+        &self.levels
+    }
+
+    // actions are numbered fills first, then empties, then pours for every pair of jugs
+    fn decode(&self, action: &Action) -> JugAction {
+        let n = self.capacities.len();
+        match action.0 {
+            i if i < n => JugAction::Fill(i),
+            i if i < 2 * n => JugAction::Empty(i - n),
+            i => JugAction::Pour((i - 2 * n) / n, (i - 2 * n) % n),
+        }
+    }
+
+    fn encode(&self, jug_action: JugAction) -> Action {
+        let n = self.capacities.len();
+        match jug_action {
+            JugAction::Fill(i) => Action(i),
+            JugAction::Empty(i) => Action(n + i),
+            JugAction::Pour(from, to) => Action(2 * n + from * n + to),
+        }
+    }
+
+    fn is_useful(&self, jug_action: JugAction) -> bool {
+        match jug_action {
+            JugAction::Fill(i) => self.levels[i] < self.capacities[i],
+            JugAction::Empty(i) => self.levels[i] > 0,
+            JugAction::Pour(from, to) => {
+                from != to && self.levels[from] > 0 && self.levels[to] < self.capacities[to]
+            }
+        }
+    }
+}
+
+impl SearchProblem for WaterJugs {
+    fn actions(&self) -> Vec<Action> {
+        let n = self.capacities.len();
+
+        (0..n)
+            .map(JugAction::Fill)
+            .chain((0..n).map(JugAction::Empty))
+            .chain((0..n * n).map(|i| JugAction::Pour(i / n, i % n)))
+            .filter(|&jug_action| self.is_useful(jug_action))
+            .map(|jug_action| self.encode(jug_action))
+            .collect()
+    }
+
+    fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
+        let mut next = self.clone();
+
+        match self.decode(action) {
+            JugAction::Fill(i) => next.levels[i] = self.capacities[i],
+            JugAction::Empty(i) => next.levels[i] = 0,
+            JugAction::Pour(from, to) => {
+                let amount = self.levels[from].min(self.capacities[to] - self.levels[to]);
+                next.levels[from] -= amount;
+                next.levels[to] += amount;
+            }
+        }
+
+        Box::new(next)
+    }
+
+    fn test_goal(&self) -> bool {
+        self.levels.contains(&self.goal)
+    }
+
+    fn path_cost(&self) -> u32 {
+        1
+    }
+
+    // 0 at the goal, 1 otherwise
+    fn value(&self) -> u32 {
+        if self.test_goal() {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn as_string(&self) -> String {
+        format!("WaterJugs(levels: {:?})", self.levels)
+    }
+
+    fn hash_code(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        format!("{:?}", self.decode(action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::uninformed::{breadth_first_search, depth_first_search};
+
+    #[test]
+    fn test_measure_four_gallons_with_three_and_five() {
+        let solution = breadth_first_search(Box::new(WaterJugs::new(vec![3, 5], 4))).unwrap();
+        let problem = WaterJugs::new(vec![3, 5], 4);
+        let names: Vec<String> = solution
+            .solution()
+            .iter()
+            .skip(1)
+            .map(|action| problem.action_name(action))
+            .collect();
+
+        assert_eq!(6, solution.depth());
+        assert_eq!("Fill(1)", names[0]);
+    }
+
+    #[test]
+    fn test_odd_amount_can_not_be_measured_with_even_jugs() {
+        assert!(depth_first_search(Box::new(WaterJugs::new(vec![2, 4], 3))).is_none());
+    }
+
+    #[test]
+    fn test_pour_stops_when_target_jug_is_full() {
+        let problem = WaterJugs::new(vec![3, 5], 1);
+        let full = problem.result(&problem.encode(JugAction::Fill(1)));
+        let poured = full.result(&problem.encode(JugAction::Pour(1, 0)));
+
+        assert_eq!("WaterJugs(levels: [3, 2])", poured.as_string());
+    }
+}
@@ -0,0 +1,117 @@
+//! Small seedable pseudo random number generator;
+//! the same seed always produces the same sequence, which keeps the stochastic
+//! algorithms reproducible in tests without pulling in external crates.
+//! It is xorshift64* seeded with splitmix64, good enough for search - not for cryptography.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // splitmix64 spreads similar seeds apart and never leaves zero state
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        Random {
+            state: if z == 0 { 0x9E37_79B9_7F4A_7C15 } else { z },
+        }
+    }
+
+    /// seeds generator from the current time, for runs that don't need to be repeatable
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+
+        Random::new(nanos)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// returns uniformly distributed number in range [0, upper)
+    pub fn gen_range(&mut self, upper: usize) -> usize {
+        assert!(upper > 0, "range must not be empty");
+
+        (self.gen_f64() * upper as f64) as usize % upper
+    }
+
+    /// returns uniformly distributed number in range [0, 1)
+    pub fn gen_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// returns true with the given probability
+    pub fn gen_bool(&mut self, probability: f64) -> bool {
+        self.gen_f64() < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.gen_range(items.len())])
+        }
+    }
+
+    /// Fisher-Yates shuffle
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.gen_range(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_same_seed_gives_same_sequence() {
+        let mut first = Random::new(42);
+        let mut second = Random::new(42);
+
+        for _ in 0..10 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_random_different_seeds_give_different_sequences() {
+        assert_ne!(Random::new(1).next_u64(), Random::new(2).next_u64());
+    }
+
+    #[test]
+    fn test_random_gen_range_stays_in_bounds() {
+        let mut rng = Random::new(7);
+
+        for _ in 0..1000 {
+            assert!(rng.gen_range(9) < 9);
+            let x = rng.gen_f64();
+            assert!((0.0..1.0).contains(&x));
+        }
+    }
+
+    #[test]
+    fn test_random_shuffle_keeps_all_items() {
+        let mut rng = Random::new(3);
+        let mut items = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+
+        rng.shuffle(&mut items);
+        items.sort();
+
+        assert_eq!([0, 1, 2, 3, 4, 5, 6, 7, 8], items);
+    }
+}
@@ -2,7 +2,15 @@ use std::cmp::Ordering;
 use std::fmt;
 use std::rc::Rc;
 
+pub mod belief;
 pub mod frontiers;
+pub mod graphviz;
+pub mod informed;
+pub mod local;
+pub mod nondeterministic;
+pub mod observers;
+pub mod online;
+pub mod steps;
 pub mod uninformed;
 
 pub trait SearchProblem {
@@ -14,6 +22,11 @@ pub trait SearchProblem {
     fn value(&self) -> u32;
     fn as_string(&self) -> String;
     fn hash_code(&self) -> u64; // used for comparition
+
+    // human readable name of an action, used in traces and exported graphs
+    fn action_name(&self, action: &Action) -> String {
+        action.0.to_string()
+    }
 }
 
 impl fmt::Debug for dyn SearchProblem {
@@ -55,7 +68,7 @@ impl SearchNode {
 
     pub fn child_node(parent: &Rc<SearchNode>, action: Action) -> SearchNode {
         let new_problem = parent.item().result(&action);
-        let new_path_cost = parent.item().path_cost() + new_problem.path_cost();
+        let new_path_cost = parent.path_cost() + new_problem.path_cost();
 
         SearchNode {
             parent: Some(parent.clone()),
@@ -95,6 +108,10 @@ impl SearchNode {
         self.depth
     }
 
+    pub fn path_cost(&self) -> u32 {
+        self.path_cost
+    }
+
     pub fn solution(&self) -> Vec<Action> {
         self.path()
             .iter()
@@ -120,6 +137,17 @@ impl SearchNode {
     }
 }
 
+impl fmt::Debug for SearchNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SearchNode")
+            .field("state", &self.item().as_string())
+            .field("action", &self.action)
+            .field("depth", &self.depth)
+            .field("path_cost", &self.path_cost)
+            .finish()
+    }
+}
+
 impl Eq for SearchNode {}
 
 impl PartialEq for SearchNode {
@@ -0,0 +1,219 @@
+//! Searching with no observation
+//! A sensorless agent only knows the set of physical states it may be in, its belief state.
+//! `BeliefState` wraps the physical problems into a `SearchProblem` over such sets:
+//! an action is applied to every member and the goal is reached when all members are goals,
+//! so the uninformed and informed searches work on it unchanged.
+//! source:
+//! AIMA 3rd edition, chapter 4.4.1
+
+use std::rc::Rc;
+
+use crate::search::{Action, SearchProblem};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BeliefActions {
+    /// any action legal in some member; members where it is illegal stay where they are
+    Union,
+    /// only actions legal in every member
+    Intersection,
+}
+
+#[derive(Clone)]
+pub struct BeliefState {
+    // members ordered by hash code without duplicates, so equal sets compare equal
+    members: Vec<Rc<Box<dyn SearchProblem>>>,
+    actions: BeliefActions,
+}
+
+impl BeliefState {
+    pub fn new(members: Vec<Box<dyn SearchProblem>>) -> Result<Self, String> {
+        if members.is_empty() {
+            return Err("belief state must have at least one member".to_string());
+        }
+
+        Ok(BeliefState::from_members(
+            members.into_iter().map(Rc::new).collect(),
+            BeliefActions::Union,
+        ))
+    }
+
+    pub fn with_actions(mut self, actions: BeliefActions) -> Self {
+        self.actions = actions;
+        self
+    }
+
+    fn from_members(mut members: Vec<Rc<Box<dyn SearchProblem>>>, actions: BeliefActions) -> Self {
+        members.sort_by_key(|member| member.hash_code());
+        members.dedup_by_key(|member| member.hash_code());
+
+        BeliefState { members, actions }
+    }
+
+    pub fn members(&self) -> &[Rc<Box<dyn SearchProblem>>] {
+        &self.members
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+impl SearchProblem for BeliefState {
+    fn actions(&self) -> Vec<Action> {
+        let mut actions: Vec<Action> = vec![];
+
+        for member in self.members.iter() {
+            for action in member.actions() {
+                if !actions.contains(&action) {
+                    actions.push(action);
+                }
+            }
+        }
+        if self.actions == BeliefActions::Intersection {
+            actions.retain(|action| {
+                self.members
+                    .iter()
+                    .all(|member| member.actions().contains(action))
+            });
+        }
+        actions.sort_by_key(|action| action.0);
+
+        actions
+    }
+
+    fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
+        let members = self
+            .members
+            .iter()
+            .map(|member| {
+                if member.actions().contains(action) {
+                    Rc::new(member.result(action))
+                } else {
+                    member.clone()
+                }
+            })
+            .collect();
+
+        Box::new(BeliefState::from_members(members, self.actions))
+    }
+
+    fn test_goal(&self) -> bool {
+        self.members.iter().all(|member| member.test_goal())
+    }
+
+    // the most expensive step among the members
+    fn path_cost(&self) -> u32 {
+        self.members
+            .iter()
+            .map(|member| member.path_cost())
+            .max()
+            .unwrap_or(0)
+    }
+
+    // every member has to reach the goal, so the largest estimate is still admissible
+    fn value(&self) -> u32 {
+        self.members
+            .iter()
+            .map(|member| member.value())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn as_string(&self) -> String {
+        let members: Vec<String> = self.members.iter().map(|m| m.as_string()).collect();
+
+        format!("{{{}}}", members.join(", "))
+    }
+
+    fn hash_code(&self) -> u64 {
+        // FNV-1a over the ordered member hashes
+        self.members
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325, |hash: u64, member| {
+                (hash ^ member.hash_code()).wrapping_mul(0x0100_0000_01b3)
+            })
+    }
+
+    fn action_name(&self, action: &Action) -> String {
+        self.members
+            .iter()
+            .find(|member| member.actions().contains(action))
+            .map_or_else(|| action.0.to_string(), |m| m.action_name(action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eight_puzzle::{EightPuzzle, DEFAULT_GOAL};
+    use crate::problems::vacuum::VacuumWorld;
+    use crate::search::uninformed::breadth_first_search;
+
+    fn vacuum_belief(worlds: Vec<VacuumWorld>) -> BeliefState {
+        BeliefState::new(
+            worlds
+                .into_iter()
+                .map(|world| Box::new(world) as Box<dyn SearchProblem>)
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sensorless_vacuum_world_is_solved_in_four_steps() {
+        let belief = vacuum_belief(VacuumWorld::all_states(2));
+        let solution = breadth_first_search(Box::new(belief.clone())).unwrap();
+        let names: Vec<String> = solution.solution()[1..]
+            .iter()
+            .map(|action| belief.action_name(action))
+            .collect();
+
+        assert_eq!(8, belief.len());
+        assert_eq!(vec!["Left", "Suck", "Right", "Suck"], names);
+    }
+
+    #[test]
+    fn test_members_merge_after_an_action() {
+        let belief = vacuum_belief(VacuumWorld::all_states(2));
+        let moved = belief.result(&Action(0));
+        let duplicates =
+            vacuum_belief(vec![VacuumWorld::two_squares(), VacuumWorld::two_squares()]);
+
+        // moving left forgets where the agent was
+        assert_eq!(4, moved.as_string().matches("VacuumWorld").count());
+        assert_ne!(belief.hash_code(), moved.hash_code());
+        assert_eq!(1, duplicates.len());
+    }
+
+    #[test]
+    fn test_illegal_moves_leave_eight_puzzle_members_in_place() {
+        let solved = EightPuzzle::from_row(DEFAULT_GOAL);
+        let one_away = EightPuzzle::from_row([1, 2, 3, 4, 5, 6, 7, 0, 8]);
+        let belief = BeliefState::new(vec![Box::new(solved), Box::new(one_away)]).unwrap();
+        let solution = breadth_first_search(Box::new(belief.clone())).unwrap();
+
+        assert!(!belief.test_goal());
+        assert_eq!("Right", belief.action_name(&solution.solution()[1]));
+        assert_eq!(1, solution.depth());
+    }
+
+    #[test]
+    fn test_intersection_offers_only_common_actions() {
+        let corner = || Box::new(EightPuzzle::from_row(DEFAULT_GOAL));
+        let center = || Box::new(EightPuzzle::from_row([1, 2, 3, 4, 0, 5, 6, 7, 8]));
+        let union = BeliefState::new(vec![corner(), center()]).unwrap();
+        let intersection = union.clone().with_actions(BeliefActions::Intersection);
+
+        assert_eq!(4, union.actions().len());
+        assert_eq!(2, intersection.actions().len());
+    }
+
+    #[test]
+    fn test_empty_belief_state_is_rejected() {
+        assert!(BeliefState::new(vec![]).is_err());
+    }
+}
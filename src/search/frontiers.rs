@@ -1,4 +1,5 @@
 use crate::search::SearchNode;
+use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::collections::VecDeque;
 
@@ -10,6 +11,7 @@ pub trait Frontier {
     fn len(&self) -> usize;
 }
 
+#[derive(Default)]
 pub struct StackFrontier {
     collection: Vec<SearchNode>,
 }
@@ -39,6 +41,7 @@ impl Frontier for StackFrontier {
 }
 
 /// it works like pipe - first thing that go in, will come out first
+#[derive(Default)]
 pub struct QueueFrontier {
     collection: VecDeque<SearchNode>,
 }
@@ -70,6 +73,7 @@ impl Frontier for QueueFrontier {
 }
 
 /// PriorityFrontier takes best node first
+#[derive(Default)]
 pub struct PriorityFrontier {
     collection: BinaryHeap<SearchNode>, // relies default impl of Ord
 }
@@ -100,6 +104,77 @@ impl Frontier for PriorityFrontier {
     }
 }
 
+struct ScoredNode {
+    score: u32,
+    order: usize,
+    node: SearchNode,
+}
+
+impl Eq for ScoredNode {}
+
+impl PartialEq for ScoredNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.score == other.score && self.order == other.order
+    }
+}
+
+impl Ord for ScoredNode {
+    // reverse ordering makes min-heap; ties are taken in insertion order
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .score
+            .cmp(&self.score)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+impl PartialOrd for ScoredNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// BestFirstFrontier takes the node with the lowest score of evaluation function f(n) first
+pub struct BestFirstFrontier {
+    collection: BinaryHeap<ScoredNode>,
+    eval: fn(&SearchNode) -> u32,
+    counter: usize,
+}
+
+impl BestFirstFrontier {
+    pub fn new(eval: fn(&SearchNode) -> u32) -> Self {
+        BestFirstFrontier {
+            collection: BinaryHeap::new(),
+            eval,
+            counter: 0,
+        }
+    }
+}
+
+impl Frontier for BestFirstFrontier {
+    fn add(&mut self, node: SearchNode) {
+        let score = (self.eval)(&node);
+        self.counter += 1;
+        self.collection.push(ScoredNode {
+            score,
+            order: self.counter,
+            node,
+        });
+    }
+    fn remove(&mut self) -> Option<SearchNode> {
+        self.collection.pop().map(|scored| scored.node)
+    }
+    fn is_empty(&self) -> bool {
+        self.collection.is_empty()
+    }
+    fn contains(&self, other: &SearchNode) -> bool {
+        self.collection.iter().any(|scored| scored.node.eq(other))
+    }
+    fn len(&self) -> usize {
+        self.collection.len()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,7 +189,7 @@ mod tests {
             vec![]
         }
 
-        fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
+        fn result(&self, _action: &Action) -> Box<dyn SearchProblem> {
             Box::new(TestSearchProblem { item: 0 })
         }
 
@@ -303,4 +378,50 @@ mod tests {
         frontier.add(test_node.clone());
         assert!(frontier.remove().is_some());
     }
+
+    // BestFirstFrontier
+
+    fn score_by_value(node: &SearchNode) -> u32 {
+        node.item().value()
+    }
+
+    #[test]
+    fn test_best_first_frontier_create_new_empty_frontier() {
+        assert!(BestFirstFrontier::new(score_by_value).is_empty());
+    }
+
+    #[test]
+    fn test_best_first_frontier_contains_true_if_element_exists() {
+        let mut frontier = BestFirstFrontier::new(score_by_value);
+        let test_node = make_test_node(11);
+
+        frontier.add(test_node.clone());
+        assert!(frontier.contains(&test_node));
+        assert_eq!(1, frontier.len());
+    }
+
+    #[test]
+    fn test_best_first_frontier_removes_lowest_score_first() {
+        let mut frontier = BestFirstFrontier::new(score_by_value);
+
+        frontier.add(make_test_node(12));
+        frontier.add(make_test_node(3));
+        frontier.add(make_test_node(7));
+
+        assert_eq!(3, frontier.remove().unwrap().item().value());
+        assert_eq!(7, frontier.remove().unwrap().item().value());
+        assert_eq!(12, frontier.remove().unwrap().item().value());
+        assert!(frontier.remove().is_none());
+    }
+
+    #[test]
+    fn test_best_first_frontier_keeps_insertion_order_for_ties() {
+        let mut frontier = BestFirstFrontier::new(|_| 0);
+
+        frontier.add(make_test_node(1));
+        frontier.add(make_test_node(2));
+
+        assert_eq!(1, frontier.remove().unwrap().item().value());
+        assert_eq!(2, frontier.remove().unwrap().item().value());
+    }
 }
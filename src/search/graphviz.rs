@@ -0,0 +1,324 @@
+//! Records what a search did and exports it as a Graphviz DOT graph;
+//! render it with `dot -Tsvg search.dot -o search.svg`
+//!
+//! expanded nodes are filled and numbered in the order of expansion,
+//! pruned duplicates are dashed and the solution path is drawn in red.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::search::observers::SearchObserver;
+use crate::search::{SearchNode, SearchProblem};
+
+pub const DEFAULT_MAX_NODES: usize = 1000;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum DotMode {
+    /// every search node is drawn separately, following the parent links
+    Tree,
+    /// search nodes with the same state are merged into a single graph node
+    Graph,
+}
+
+struct RecordedNode {
+    item: Rc<Box<dyn SearchProblem>>,
+    expanded_order: Option<usize>,
+    pruned: bool,
+}
+
+struct RecordedEdge {
+    from: usize,
+    to: usize,
+    label: String,
+    pruned: bool,
+}
+
+/// Observer that keeps up to `max_nodes` nodes of the search tree or graph
+pub struct DotRecorder {
+    mode: DotMode,
+    max_nodes: usize,
+    nodes: Vec<RecordedNode>,
+    ids: HashMap<u64, usize>,
+    edges: Vec<RecordedEdge>,
+    expanded: usize,
+    truncated: bool,
+}
+
+impl DotRecorder {
+    pub fn new(mode: DotMode, max_nodes: usize) -> Self {
+        DotRecorder {
+            mode,
+            max_nodes,
+            nodes: vec![],
+            ids: HashMap::new(),
+            edges: vec![],
+            expanded: 0,
+            truncated: false,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// true if some nodes were dropped after reaching the size cap
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P, solution: Option<&SearchNode>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        self.write_dot(&mut writer, solution)?;
+        writer.flush()
+    }
+
+    pub fn write_dot(&self, w: &mut dyn Write, solution: Option<&SearchNode>) -> io::Result<()> {
+        let (path_nodes, path_edges) = self.solution_ids(solution);
+
+        writeln!(w, "digraph search {{")?;
+        writeln!(w, "    node [shape=box, fontname=\"monospace\"];")?;
+        if self.truncated {
+            writeln!(w, "    // truncated after {} nodes", self.max_nodes)?;
+        }
+
+        for (id, node) in self.nodes.iter().enumerate() {
+            let mut label = node.item.as_string();
+            let mut attrs = vec![];
+
+            if let Some(order) = node.expanded_order {
+                label = format!("#{} {}", order, label);
+                attrs.push("style=filled".to_string());
+                attrs.push("fillcolor=lightblue".to_string());
+            } else if node.pruned {
+                attrs.push("style=dashed".to_string());
+                attrs.push("fontcolor=gray".to_string());
+                attrs.push("color=gray".to_string());
+            }
+            if path_nodes.contains(&id) {
+                attrs.push("color=red".to_string());
+                attrs.push("penwidth=2".to_string());
+            }
+            attrs.insert(0, format!("label={}", dot_string(&label)));
+
+            writeln!(w, "    n{} [{}];", id, attrs.join(", "))?;
+        }
+
+        for edge in self.edges.iter() {
+            let mut attrs = vec![format!("label={}", dot_string(&edge.label))];
+
+            if edge.pruned {
+                attrs.push("style=dashed".to_string());
+                attrs.push("color=gray".to_string());
+            }
+            if path_edges.contains(&(edge.from, edge.to)) {
+                attrs.push("color=red".to_string());
+                attrs.push("penwidth=2".to_string());
+            }
+
+            writeln!(
+                w,
+                "    n{} -> n{} [{}];",
+                edge.from,
+                edge.to,
+                attrs.join(", ")
+            )?;
+        }
+
+        writeln!(w, "}}")
+    }
+
+    fn node_key(&self, node: &SearchNode) -> u64 {
+        match self.mode {
+            DotMode::Tree => Rc::as_ptr(node.item()) as *const () as usize as u64,
+            DotMode::Graph => node.item().hash_code(),
+        }
+    }
+
+    fn add_node(&mut self, node: &SearchNode, pruned: bool) -> Option<usize> {
+        if self.nodes.len() >= self.max_nodes {
+            self.truncated = true;
+            return None;
+        }
+
+        let id = self.nodes.len();
+        self.nodes.push(RecordedNode {
+            item: node.item().clone(),
+            expanded_order: None,
+            pruned,
+        });
+
+        Some(id)
+    }
+
+    fn find_or_add(&mut self, node: &SearchNode) -> Option<usize> {
+        let key = self.node_key(node);
+        if let Some(&id) = self.ids.get(&key) {
+            return Some(id);
+        }
+
+        let id = self.add_node(node, false)?;
+        self.ids.insert(key, id);
+        Some(id)
+    }
+
+    fn has_edge(&self, parent: &SearchNode, to: usize) -> bool {
+        match self.ids.get(&self.node_key(parent)) {
+            Some(&from) => self.edges.iter().any(|e| e.from == from && e.to == to),
+            None => false,
+        }
+    }
+
+    fn add_edge(&mut self, parent: &SearchNode, to: usize, child: &SearchNode, pruned: bool) {
+        let from = match self.ids.get(&self.node_key(parent)) {
+            Some(&id) => id,
+            None => return,
+        };
+        let label = match child.action() {
+            Some(action) => parent.item().action_name(action),
+            None => String::new(),
+        };
+
+        self.edges.push(RecordedEdge {
+            from,
+            to,
+            label,
+            pruned,
+        });
+    }
+
+    fn solution_ids(
+        &self,
+        solution: Option<&SearchNode>,
+    ) -> (HashSet<usize>, HashSet<(usize, usize)>) {
+        let mut path_nodes = HashSet::new();
+        let mut path_edges = HashSet::new();
+
+        if let Some(goal_node) = solution {
+            let ids: Vec<Option<usize>> = goal_node
+                .path()
+                .iter()
+                .map(|node| self.ids.get(&self.node_key(node)).copied())
+                .collect();
+
+            for id in ids.iter().flatten() {
+                path_nodes.insert(*id);
+            }
+            // path goes from the goal back to the root
+            for pair in ids.windows(2) {
+                if let (Some(child), Some(parent)) = (pair[0], pair[1]) {
+                    path_edges.insert((parent, child));
+                }
+            }
+        }
+
+        (path_nodes, path_edges)
+    }
+}
+
+impl SearchObserver for DotRecorder {
+    fn on_goal_test(&mut self, node: &SearchNode, _is_goal: bool) {
+        self.find_or_add(node);
+    }
+
+    fn on_expand(&mut self, node: &SearchNode) {
+        self.expanded += 1;
+
+        let order = self.expanded;
+        if let Some(id) = self.find_or_add(node) {
+            let recorded = &mut self.nodes[id];
+            recorded.expanded_order.get_or_insert(order);
+        }
+    }
+
+    fn on_generate(&mut self, parent: &SearchNode, child: &SearchNode) {
+        let key = self.node_key(child);
+        let is_new = !self.ids.contains_key(&key);
+
+        if let Some(id) = self.find_or_add(child) {
+            // graph mode merges states, so the same edge may be generated many times
+            if is_new || self.mode == DotMode::Tree || !self.has_edge(parent, id) {
+                self.add_edge(parent, id, child, false);
+            }
+        }
+    }
+
+    fn on_prune(&mut self, parent: &SearchNode, child: &SearchNode) {
+        let id = match self.mode {
+            DotMode::Tree => self.add_node(child, true),
+            DotMode::Graph => self.ids.get(&self.node_key(child)).copied(),
+        };
+
+        if let Some(id) = id {
+            self.add_edge(parent, id, child, true);
+        }
+    }
+}
+
+fn dot_string(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eight_puzzle::EightPuzzle;
+    use crate::search::uninformed::breadth_first_search_observed;
+
+    fn record(mode: DotMode, max_nodes: usize) -> (DotRecorder, SearchNode) {
+        let puzzle = Box::new(EightPuzzle::from_row([1, 2, 3, 4, 0, 5, 7, 8, 6]));
+        let mut recorder = DotRecorder::new(mode, max_nodes);
+        let solution = breadth_first_search_observed(puzzle, &mut recorder).unwrap();
+
+        (recorder, solution)
+    }
+
+    fn render(recorder: &DotRecorder, solution: &SearchNode) -> String {
+        let mut out = vec![];
+        recorder.write_dot(&mut out, Some(solution)).unwrap();
+
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_dot_recorder_writes_tree_with_solution_path() {
+        let (recorder, solution) = record(DotMode::Tree, DEFAULT_MAX_NODES);
+        let dot = render(&recorder, &solution);
+
+        assert!(dot.starts_with("digraph search {"));
+        assert!(dot.contains("n0 [label=\"#1 EightPuzzle(state: [1, 2, 3, 4, 0, 5, 7, 8, 6]\""));
+        assert!(dot.contains("[label=\"Right\", color=red, penwidth=2]"));
+        assert!(dot.contains("style=dashed"));
+        assert!(!recorder.is_truncated());
+    }
+
+    #[test]
+    fn test_dot_recorder_graph_mode_merges_same_states() {
+        let (tree, _) = record(DotMode::Tree, DEFAULT_MAX_NODES);
+        let (graph, _) = record(DotMode::Graph, DEFAULT_MAX_NODES);
+
+        assert!(graph.len() < tree.len());
+    }
+
+    #[test]
+    fn test_dot_recorder_stops_at_size_cap() {
+        let (recorder, solution) = record(DotMode::Tree, 3);
+        let dot = render(&recorder, &solution);
+
+        assert_eq!(3, recorder.len());
+        assert!(recorder.is_truncated());
+        assert!(dot.contains("// truncated after 3 nodes"));
+    }
+}
@@ -0,0 +1,139 @@
+//! Informed search strategies
+//! These algorithms use the heuristic estimate `SearchProblem::value()` to decide
+//! which nodes are worth expanding first.
+//! source:
+//! https://github.com/aimacode/aima-python/blob/master/search.py
+
+use std::collections::HashSet;
+
+use crate::search::frontiers::{BestFirstFrontier, Frontier};
+use crate::search::observers::{NoopObserver, SearchObserver};
+use crate::search::uninformed::traverse_frontier;
+use crate::search::{SearchNode, SearchProblem};
+
+/// A* search expands the node with the lowest f(n) = g(n) + h(n),
+/// path cost so far plus the heuristic estimate of the remaining cost.
+/// It is optimal, if the heuristic never overestimates and is consistent.
+pub fn astar_search(problem: Box<dyn SearchProblem>) -> Option<SearchNode> {
+    astar_search_observed(problem, &mut NoopObserver)
+}
+
+pub fn astar_search_observed(
+    problem: Box<dyn SearchProblem>,
+    observer: &mut dyn SearchObserver,
+) -> Option<SearchNode> {
+    // dead end problems may return u32::MAX as the heuristic value
+    let mut frontier =
+        BestFirstFrontier::new(|node| node.path_cost().saturating_add(node.item().value()));
+
+    frontier.add(SearchNode::root(problem));
+    traverse_frontier(&mut frontier, observer)
+}
+
+/// Breadth first search that keeps only `width` best nodes of every level;
+/// nodes are ranked by the heuristic value, ties keep the order of generation.
+/// The beam width bounds time and memory per level, but the search is neither
+/// complete nor optimal - a narrow beam may drop the only path to the goal.
+pub fn beam_search(problem: Box<dyn SearchProblem>, width: usize) -> Option<SearchNode> {
+    beam_search_observed(problem, width, &mut NoopObserver)
+}
+
+pub fn beam_search_observed(
+    problem: Box<dyn SearchProblem>,
+    width: usize,
+    observer: &mut dyn SearchObserver,
+) -> Option<SearchNode> {
+    let mut explored: HashSet<u64> = HashSet::new();
+    let mut level = vec![SearchNode::root(problem)];
+
+    while !level.is_empty() {
+        let mut next_level: Vec<SearchNode> = vec![];
+        let mut next_states: HashSet<u64> = HashSet::new();
+
+        for current_node in level.into_iter() {
+            let is_goal = current_node.is_goal();
+            observer.on_goal_test(&current_node, is_goal);
+
+            if is_goal {
+                return Some(current_node);
+            }
+            if observer.should_stop() {
+                return None;
+            }
+
+            let child_nodes = current_node.expand();
+            explored.insert(current_node.item().hash_code());
+            observer.on_expand(&current_node);
+
+            for child_node in child_nodes.into_iter() {
+                let hash = child_node.item().hash_code();
+                if explored.contains(&hash) || !next_states.insert(hash) {
+                    observer.on_prune(&current_node, &child_node);
+                } else {
+                    observer.on_generate(&current_node, &child_node);
+                    next_level.push(child_node);
+                }
+            }
+        }
+
+        next_level.sort_by_key(|node| node.item().value());
+        next_level.truncate(width);
+        level = next_level;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eight_puzzle::{EightPuzzle, Heuristic};
+    use crate::search::observers::CountingObserver;
+    use crate::search::uninformed::breadth_first_search_observed;
+
+    fn make_puzzle(row: [u8; 9]) -> Box<dyn SearchProblem> {
+        Box::new(EightPuzzle::from_row(row).with_heuristic(Heuristic::ManhattanDistance))
+    }
+
+    #[test]
+    fn test_astar_search_finds_shortest_path() {
+        let board = [5, 1, 2, 6, 3, 0, 4, 7, 8];
+        let mut astar_counter = CountingObserver::new();
+        let mut bfs_counter = CountingObserver::new();
+
+        let solution = astar_search_observed(make_puzzle(board), &mut astar_counter).unwrap();
+        breadth_first_search_observed(make_puzzle(board), &mut bfs_counter).unwrap();
+
+        assert_eq!(11, solution.depth());
+        assert_eq!(11, solution.path_cost());
+        assert!(astar_counter.expanded < bfs_counter.expanded);
+    }
+
+    #[test]
+    fn test_beam_search_finds_shortest_path_with_wide_beam() {
+        let board = [1, 6, 2, 5, 3, 0, 4, 7, 8];
+        let solution = beam_search(make_puzzle(board), 1000).unwrap();
+
+        assert_eq!(9, solution.depth());
+    }
+
+    #[test]
+    fn test_beam_search_expands_less_than_breadth_first() {
+        let board = [4, 1, 3, 7, 2, 6, 5, 8, 0];
+        let mut beam_counter = CountingObserver::new();
+        let mut bfs_counter = CountingObserver::new();
+
+        let solution = beam_search_observed(make_puzzle(board), 3, &mut beam_counter).unwrap();
+        breadth_first_search_observed(make_puzzle(board), &mut bfs_counter).unwrap();
+
+        assert!(solution.depth() >= 8);
+        assert!(beam_counter.expanded < bfs_counter.expanded);
+    }
+
+    #[test]
+    fn test_beam_search_returns_root_goal() {
+        let solution = beam_search(make_puzzle([1, 2, 3, 4, 5, 6, 7, 8, 0]), 1).unwrap();
+
+        assert_eq!(0, solution.depth());
+    }
+}
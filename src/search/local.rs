@@ -0,0 +1,600 @@
+//! Local search strategies
+//! Unlike the systematic strategies in `uninformed`, local search keeps only the current
+//! state and moves to its neighbors; it uses little memory, but may get stuck.
+//! AIMA describes hill climbing as maximizing the objective, here `SearchProblem::value()`
+//! is treated as a cost (the heuristic estimate h(n)) and the algorithms climb down to 0.
+//! source:
+//! https://github.com/aimacode/aima-python/blob/master/search.py
+
+use std::collections::HashSet;
+
+use crate::random::Random;
+use crate::search::{SearchNode, SearchProblem};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LocalSearchOutcome {
+    /// current state passed the goal test
+    Goal,
+    /// every neighbor is worse than the current state
+    LocalOptimum,
+    /// no neighbor is better, but some are as good as the current state
+    Plateau,
+    /// search gave up after the maximum number of steps
+    StepLimit,
+}
+
+#[derive(Debug, Clone)]
+pub struct LocalSearchResult {
+    /// final node, its path is the trajectory of the search
+    pub node: SearchNode,
+    pub outcome: LocalSearchOutcome,
+    /// number of moves made; with restarts, number of moves of the returned run
+    pub steps: usize,
+    /// number of restarts made before the search finished
+    pub restarts: usize,
+}
+
+impl LocalSearchResult {
+    fn new(node: SearchNode, outcome: LocalSearchOutcome) -> Self {
+        let steps = node.depth();
+
+        LocalSearchResult {
+            node,
+            outcome,
+            steps,
+            restarts: 0,
+        }
+    }
+
+    pub fn is_goal(&self) -> bool {
+        self.outcome == LocalSearchOutcome::Goal
+    }
+
+    pub fn value(&self) -> u32 {
+        value(&self.node)
+    }
+}
+
+/// Always moves to the best neighbor; stops when no neighbor improves the current state.
+/// `max_sideways` allows that many consecutive moves to equally good neighbors,
+/// which lets the search walk over shoulders of a plateau.
+pub fn steepest_ascent_hill_climbing(
+    problem: Box<dyn SearchProblem>,
+    max_sideways: usize,
+) -> LocalSearchResult {
+    let mut current = SearchNode::root(problem);
+    let mut sideways = 0;
+
+    loop {
+        if current.is_goal() {
+            return LocalSearchResult::new(current, LocalSearchOutcome::Goal);
+        }
+
+        let neighbor = match current.expand().into_iter().min_by_key(value) {
+            Some(node) => node,
+            None => return LocalSearchResult::new(current, LocalSearchOutcome::LocalOptimum),
+        };
+
+        if value(&neighbor) > value(&current) {
+            return LocalSearchResult::new(current, LocalSearchOutcome::LocalOptimum);
+        }
+
+        if value(&neighbor) == value(&current) {
+            if sideways >= max_sideways {
+                return LocalSearchResult::new(current, LocalSearchOutcome::Plateau);
+            }
+            sideways += 1;
+        } else {
+            sideways = 0;
+        }
+
+        current = neighbor;
+    }
+}
+
+/// Generates neighbors in random order and takes the first one that is better
+/// than the current state; useful when a state has too many neighbors to evaluate.
+pub fn first_choice_hill_climbing(
+    problem: Box<dyn SearchProblem>,
+    rng: &mut Random,
+) -> LocalSearchResult {
+    let mut current = SearchNode::root(problem);
+
+    loop {
+        if current.is_goal() {
+            return LocalSearchResult::new(current, LocalSearchOutcome::Goal);
+        }
+
+        let mut neighbors = current.expand();
+        rng.shuffle(&mut neighbors);
+
+        let current_value = value(&current);
+        match neighbors.iter().position(|n| value(n) < current_value) {
+            Some(i) => current = neighbors.swap_remove(i),
+            None => return stuck(current, &neighbors),
+        }
+    }
+}
+
+/// Chooses randomly among the better neighbors;
+/// the probability of a move grows with how much it improves the current state.
+pub fn stochastic_hill_climbing(
+    problem: Box<dyn SearchProblem>,
+    rng: &mut Random,
+) -> LocalSearchResult {
+    let mut current = SearchNode::root(problem);
+
+    loop {
+        if current.is_goal() {
+            return LocalSearchResult::new(current, LocalSearchOutcome::Goal);
+        }
+
+        let neighbors = current.expand();
+        let current_value = value(&current);
+        let improvements: Vec<u32> = neighbors
+            .iter()
+            .map(|n| current_value.saturating_sub(value(n)))
+            .collect();
+
+        let total: u32 = improvements.iter().sum();
+        if total == 0 {
+            return stuck(current, &neighbors);
+        }
+
+        let mut pick = rng.gen_range(total as usize) as u32;
+        for (neighbor, improvement) in neighbors.into_iter().zip(improvements) {
+            if pick < improvement {
+                current = neighbor;
+                break;
+            }
+            pick -= improvement;
+        }
+    }
+}
+
+/// Runs steepest ascent hill climbing from random initial states until it finds the goal;
+/// `make_problem` creates a new random initial state for every restart.
+/// Returns the goal or the best state found after `max_restarts` restarts.
+pub fn random_restart_hill_climbing(
+    make_problem: &mut dyn FnMut(&mut Random) -> Box<dyn SearchProblem>,
+    max_restarts: usize,
+    max_sideways: usize,
+    rng: &mut Random,
+) -> LocalSearchResult {
+    let mut best: Option<LocalSearchResult> = None;
+
+    for restart in 0..=max_restarts {
+        let mut res = steepest_ascent_hill_climbing(make_problem(rng), max_sideways);
+        res.restarts = restart;
+
+        if res.is_goal() {
+            return res;
+        }
+
+        let is_better = match &best {
+            Some(best_res) => res.value() < best_res.value(),
+            None => true,
+        };
+        if is_better {
+            best = Some(res);
+        }
+    }
+
+    let mut best_res = best.expect("at least one run is always made");
+    best_res.restarts = max_restarts;
+    best_res
+}
+
+/// Keeps k best states of all successors on every step, so the states share information;
+/// the search stops at the goal or when the best successor is worse than the best current state.
+pub fn local_beam_search(
+    problem: Box<dyn SearchProblem>,
+    k: usize,
+    max_steps: usize,
+) -> LocalSearchResult {
+    beam_steps(problem, max_steps, &mut |mut successors| {
+        successors.sort_by_key(value);
+        successors.truncate(k);
+        successors
+    })
+}
+
+/// Like local beam search, but picks k successors randomly, better ones more likely;
+/// it keeps the beam from collapsing into one region of the state space.
+pub fn stochastic_beam_search(
+    problem: Box<dyn SearchProblem>,
+    k: usize,
+    max_steps: usize,
+    rng: &mut Random,
+) -> LocalSearchResult {
+    beam_steps(problem, max_steps, &mut |mut successors| {
+        let mut selected = Vec::with_capacity(k);
+
+        while selected.len() < k && !successors.is_empty() {
+            let weights: Vec<f64> = successors
+                .iter()
+                .map(|n| 1.0 / (1.0 + value(n) as f64))
+                .collect();
+            let mut pick = rng.gen_f64() * weights.iter().sum::<f64>();
+
+            let mut chosen = successors.len() - 1;
+            for (i, weight) in weights.iter().enumerate() {
+                if pick < *weight {
+                    chosen = i;
+                    break;
+                }
+                pick -= weight;
+            }
+            selected.push(successors.swap_remove(chosen));
+        }
+
+        selected
+    })
+}
+
+fn beam_steps(
+    problem: Box<dyn SearchProblem>,
+    max_steps: usize,
+    select: &mut dyn FnMut(Vec<SearchNode>) -> Vec<SearchNode>,
+) -> LocalSearchResult {
+    let mut beam = vec![SearchNode::root(problem)];
+
+    for _ in 0..max_steps {
+        if let Some(goal) = beam.iter().find(|node| node.is_goal()) {
+            return LocalSearchResult::new(goal.clone(), LocalSearchOutcome::Goal);
+        }
+
+        let mut seen = HashSet::new();
+        let successors: Vec<SearchNode> = beam
+            .iter()
+            .flat_map(|node| node.expand())
+            .filter(|node| seen.insert(node.item().hash_code()))
+            .collect();
+
+        let best = best_node(&beam);
+        let next_beam = select(successors);
+        match next_beam.iter().min_by_key(|n| value(n)) {
+            Some(next_best) if value(next_best) <= value(&best) => beam = next_beam,
+            _ => return LocalSearchResult::new(best, LocalSearchOutcome::LocalOptimum),
+        }
+    }
+
+    match beam.iter().find(|node| node.is_goal()) {
+        Some(goal) => LocalSearchResult::new(goal.clone(), LocalSearchOutcome::Goal),
+        None => LocalSearchResult::new(best_node(&beam), LocalSearchOutcome::StepLimit),
+    }
+}
+
+fn best_node(nodes: &[SearchNode]) -> SearchNode {
+    nodes
+        .iter()
+        .min_by_key(|n| value(n))
+        .expect("beam is never empty")
+        .clone()
+}
+
+/// Temperature of simulated annealing at step t
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CoolingSchedule {
+    /// T(t) = t0 * alpha^t, alpha is between 0 and 1
+    Exponential { t0: f64, alpha: f64 },
+    /// T(t) = t0 - rate * t, until it reaches 0
+    Linear { t0: f64, rate: f64 },
+    /// T(t) = c / ln(t + 2), cools very slowly
+    Logarithmic { c: f64 },
+}
+
+impl CoolingSchedule {
+    pub fn temperature(&self, t: usize) -> f64 {
+        match *self {
+            CoolingSchedule::Exponential { t0, alpha } => t0 * alpha.powi(t as i32),
+            CoolingSchedule::Linear { t0, rate } => (t0 - rate * t as f64).max(0.0),
+            CoolingSchedule::Logarithmic { c } => c / ((t + 2) as f64).ln(),
+        }
+    }
+}
+
+impl Default for CoolingSchedule {
+    fn default() -> Self {
+        CoolingSchedule::Exponential {
+            t0: 20.0,
+            alpha: 0.995,
+        }
+    }
+}
+
+/// annealing stops when the temperature drops below this value
+pub const MIN_TEMPERATURE: f64 = 1e-6;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnnealingStep {
+    pub step: usize,
+    pub temperature: f64,
+    /// value of the current state before the move
+    pub energy: u32,
+    /// value of the randomly picked neighbor
+    pub candidate_energy: u32,
+    pub accepted: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AnnealingResult {
+    /// final node, its path contains only the accepted moves
+    pub node: SearchNode,
+    pub trajectory: Vec<AnnealingStep>,
+}
+
+impl AnnealingResult {
+    pub fn is_goal(&self) -> bool {
+        self.node.is_goal()
+    }
+
+    pub fn value(&self) -> u32 {
+        value(&self.node)
+    }
+
+    pub fn steps(&self) -> usize {
+        self.trajectory.len()
+    }
+
+    pub fn accepted(&self) -> usize {
+        self.trajectory.iter().filter(|step| step.accepted).count()
+    }
+
+    pub fn acceptance_rate(&self) -> f64 {
+        if self.trajectory.is_empty() {
+            0.0
+        } else {
+            self.accepted() as f64 / self.trajectory.len() as f64
+        }
+    }
+}
+
+/// Picks a random neighbor; better ones are always accepted,
+/// worse ones with probability e^(-delta/T), which shrinks as the temperature cools down.
+/// It stops at the goal, after `max_steps` steps or when the temperature drops to zero.
+pub fn simulated_annealing(
+    problem: Box<dyn SearchProblem>,
+    schedule: CoolingSchedule,
+    max_steps: usize,
+    rng: &mut Random,
+) -> AnnealingResult {
+    let mut current = SearchNode::root(problem);
+    let mut trajectory = vec![];
+
+    for step in 0..max_steps {
+        if current.is_goal() {
+            break;
+        }
+
+        let temperature = schedule.temperature(step);
+        if temperature < MIN_TEMPERATURE {
+            break;
+        }
+
+        let mut neighbors = current.expand();
+        if neighbors.is_empty() {
+            break;
+        }
+
+        let candidate = neighbors.swap_remove(rng.gen_range(neighbors.len()));
+        let energy = value(&current);
+        let candidate_energy = value(&candidate);
+        let delta = candidate_energy as f64 - energy as f64;
+        let accepted = delta < 0.0 || rng.gen_f64() < (-delta / temperature).exp();
+
+        trajectory.push(AnnealingStep {
+            step,
+            temperature,
+            energy,
+            candidate_energy,
+            accepted,
+        });
+
+        if accepted {
+            current = candidate;
+        }
+    }
+
+    AnnealingResult {
+        node: current,
+        trajectory,
+    }
+}
+
+fn value(node: &SearchNode) -> u32 {
+    node.item().value()
+}
+
+fn stuck(current: SearchNode, neighbors: &[SearchNode]) -> LocalSearchResult {
+    let current_value = value(&current);
+    let outcome = if neighbors.iter().any(|n| value(n) == current_value) {
+        LocalSearchOutcome::Plateau
+    } else {
+        LocalSearchOutcome::LocalOptimum
+    };
+
+    LocalSearchResult::new(current, outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eight_puzzle::{EightPuzzle, EightPuzzleState, Heuristic, DEFAULT_GOAL};
+
+    fn make_puzzle(row: [u8; 9]) -> Box<dyn SearchProblem> {
+        Box::new(EightPuzzle::from_row(row).with_heuristic(Heuristic::ManhattanDistance))
+    }
+
+    fn random_walk(rng: &mut Random, steps: usize) -> Box<dyn SearchProblem> {
+        let mut state = EightPuzzleState::new(DEFAULT_GOAL);
+        for _ in 0..steps {
+            let actions = state.possible_actions();
+            state = state.next_state(rng.choose(&actions).unwrap());
+        }
+
+        Box::new(EightPuzzle::new(state).with_heuristic(Heuristic::ManhattanDistance))
+    }
+
+    #[test]
+    fn test_steepest_ascent_reaches_goal_on_easy_board() {
+        let res = steepest_ascent_hill_climbing(make_puzzle([1, 2, 3, 4, 0, 5, 7, 8, 6]), 0);
+
+        assert_eq!(LocalSearchOutcome::Goal, res.outcome);
+        assert_eq!(2, res.steps);
+        assert_eq!(0, res.value());
+    }
+
+    #[test]
+    fn test_steepest_ascent_stops_at_local_optimum() {
+        let res = steepest_ascent_hill_climbing(make_puzzle([1, 0, 3, 4, 5, 6, 7, 8, 2]), 0);
+
+        assert_eq!(LocalSearchOutcome::LocalOptimum, res.outcome);
+        assert_eq!(0, res.steps);
+        assert_eq!(3, res.value());
+    }
+
+    #[test]
+    fn test_steepest_ascent_stops_at_plateau_without_sideways_moves() {
+        let puzzle = EightPuzzle::from_row([0, 2, 3, 5, 4, 6, 7, 8, 1]);
+        let res = steepest_ascent_hill_climbing(Box::new(puzzle), 0);
+
+        assert_eq!(LocalSearchOutcome::Plateau, res.outcome);
+        assert_eq!(0, res.steps);
+    }
+
+    #[test]
+    fn test_steepest_ascent_makes_sideways_moves_on_plateau() {
+        let puzzle = EightPuzzle::from_row([0, 2, 3, 5, 4, 6, 7, 8, 1]);
+        let res = steepest_ascent_hill_climbing(Box::new(puzzle), 3);
+
+        assert!(res.steps > 0);
+    }
+
+    #[test]
+    fn test_first_choice_hill_climbing_is_reproducible() {
+        let board = [4, 1, 3, 7, 2, 6, 5, 8, 0];
+        let first = first_choice_hill_climbing(make_puzzle(board), &mut Random::new(11));
+        let second = first_choice_hill_climbing(make_puzzle(board), &mut Random::new(11));
+
+        assert_eq!(first.outcome, second.outcome);
+        assert_eq!(first.node.solution(), second.node.solution());
+    }
+
+    #[test]
+    fn test_stochastic_hill_climbing_only_moves_downhill() {
+        let res = stochastic_hill_climbing(
+            make_puzzle([1, 2, 3, 4, 0, 5, 7, 8, 6]),
+            &mut Random::new(5),
+        );
+        let values: Vec<u32> = res.node.path().iter().rev().map(|n| value(n)).collect();
+
+        assert!(values.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(res.is_goal());
+    }
+
+    #[test]
+    fn test_cooling_schedules() {
+        let exponential = CoolingSchedule::Exponential {
+            t0: 10.0,
+            alpha: 0.5,
+        };
+        let linear = CoolingSchedule::Linear {
+            t0: 10.0,
+            rate: 4.0,
+        };
+        let logarithmic = CoolingSchedule::Logarithmic { c: 2.0 };
+
+        assert_eq!(2.5, exponential.temperature(2));
+        assert_eq!(2.0, linear.temperature(2));
+        assert_eq!(0.0, linear.temperature(3));
+        assert!((logarithmic.temperature(0) - 2.0 / 2f64.ln()).abs() < 1e-9);
+        assert!(logarithmic.temperature(100) < logarithmic.temperature(10));
+    }
+
+    #[test]
+    fn test_simulated_annealing_is_reproducible_with_seed() {
+        let board = [5, 1, 2, 6, 3, 0, 4, 7, 8];
+        let schedule = CoolingSchedule::default();
+        let first = simulated_annealing(make_puzzle(board), schedule, 500, &mut Random::new(9));
+        let second = simulated_annealing(make_puzzle(board), schedule, 500, &mut Random::new(9));
+
+        assert_eq!(first.trajectory, second.trajectory);
+        assert_eq!(first.node.solution(), second.node.solution());
+    }
+
+    #[test]
+    fn test_simulated_annealing_solves_easy_board() {
+        let schedule = CoolingSchedule::Exponential {
+            t0: 1.0,
+            alpha: 0.9,
+        };
+        let res = simulated_annealing(
+            make_puzzle([1, 2, 3, 4, 0, 5, 7, 8, 6]),
+            schedule,
+            1000,
+            &mut Random::new(1),
+        );
+
+        assert!(res.is_goal());
+        assert_eq!(0, res.value());
+        assert_eq!(res.accepted(), res.node.depth());
+    }
+
+    #[test]
+    fn test_simulated_annealing_stops_when_frozen() {
+        let schedule = CoolingSchedule::Linear { t0: 1.0, rate: 0.1 };
+        let res = simulated_annealing(
+            make_puzzle([8, 7, 6, 5, 4, 3, 2, 1, 0]),
+            schedule,
+            1000,
+            &mut Random::new(3),
+        );
+
+        assert!(res.steps() <= 10);
+        assert!(res.acceptance_rate() <= 1.0);
+    }
+
+    #[test]
+    fn test_local_beam_search_finds_goal() {
+        let res = local_beam_search(make_puzzle([1, 2, 3, 7, 4, 5, 0, 8, 6]), 4, 100);
+
+        assert!(res.is_goal());
+        assert_eq!(4, res.steps);
+    }
+
+    #[test]
+    fn test_local_beam_search_with_single_state_gets_stuck() {
+        let res = local_beam_search(make_puzzle([1, 0, 3, 4, 5, 6, 7, 8, 2]), 1, 100);
+
+        assert_eq!(LocalSearchOutcome::LocalOptimum, res.outcome);
+        assert_eq!(3, res.value());
+    }
+
+    #[test]
+    fn test_local_beam_search_stops_after_max_steps() {
+        let res = local_beam_search(make_puzzle([1, 2, 3, 7, 4, 5, 0, 8, 6]), 10, 2);
+
+        assert_eq!(LocalSearchOutcome::StepLimit, res.outcome);
+        assert!(res.steps <= 2);
+    }
+
+    #[test]
+    fn test_stochastic_beam_search_is_reproducible_with_seed() {
+        let board = [4, 1, 3, 7, 2, 6, 5, 8, 0];
+        let first = stochastic_beam_search(make_puzzle(board), 5, 100, &mut Random::new(8));
+        let second = stochastic_beam_search(make_puzzle(board), 5, 100, &mut Random::new(8));
+
+        assert_eq!(first.outcome, second.outcome);
+        assert_eq!(first.node.solution(), second.node.solution());
+    }
+
+    #[test]
+    fn test_random_restart_hill_climbing_finds_goal() {
+        let mut rng = Random::new(2020);
+        let res = random_restart_hill_climbing(&mut |rng| random_walk(rng, 20), 50, 10, &mut rng);
+
+        assert!(res.is_goal());
+        assert!(res.restarts <= 50);
+    }
+}
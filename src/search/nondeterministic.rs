@@ -0,0 +1,254 @@
+//! Search in nondeterministic environments
+//! An action can lead to one of several states, so the solution is a conditional plan
+//! that says what to do next for every outcome. AND-OR search fails instead of returning
+//! a plan with loops, like the book's version.
+//! source:
+//! AIMA 3rd edition, chapter 4.3, figure 4.11
+//! https://github.com/aimacode/aima-python/blob/master/search.py
+
+use std::fmt;
+
+use crate::search::Action;
+
+pub trait NondeterministicProblem {
+    fn actions(&self) -> Vec<Action>;
+    // every state the action may lead to
+    fn results(&self, action: &Action) -> Vec<Box<dyn NondeterministicProblem>>;
+    fn test_goal(&self) -> bool;
+    fn as_string(&self) -> String;
+    fn hash_code(&self) -> u64;
+    // human readable name of an action, used when printing plans
+    fn action_name(&self, action: &Action) -> String {
+        action.0.to_string()
+    }
+}
+
+impl fmt::Debug for dyn NondeterministicProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NondeterministicProblem")
+            .field("state", &self.as_string())
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionalPlan {
+    /// the goal is reached, nothing left to do
+    Done,
+    /// do the action, then follow the branch of the state it led to
+    Step {
+        action: Action,
+        name: String,
+        branches: Vec<Branch>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Branch {
+    /// `as_string` of the outcome state
+    pub state: String,
+    pub plan: ConditionalPlan,
+}
+
+impl ConditionalPlan {
+    /// plan to follow after the action led to the state, None if the plan does not expect it
+    pub fn branch(&self, state: &dyn NondeterministicProblem) -> Option<&ConditionalPlan> {
+        match self {
+            ConditionalPlan::Done => None,
+            ConditionalPlan::Step { branches, .. } => {
+                let state = state.as_string();
+                branches
+                    .iter()
+                    .find(|branch| branch.state == state)
+                    .map(|branch| &branch.plan)
+            }
+        }
+    }
+
+    /// number of actions in the worst case
+    pub fn depth(&self) -> usize {
+        match self {
+            ConditionalPlan::Done => 0,
+            ConditionalPlan::Step { branches, .. } => {
+                1 + branches
+                    .iter()
+                    .map(|branch| branch.plan.depth())
+                    .max()
+                    .unwrap_or(0)
+            }
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let pad = "    ".repeat(indent);
+
+        match self {
+            ConditionalPlan::Done => Ok(()),
+            ConditionalPlan::Step { name, branches, .. } => {
+                writeln!(f, "{}{}", pad, name)?;
+                // a single outcome needs no condition, the plan just goes on
+                if let [branch] = branches.as_slice() {
+                    return branch.plan.write(f, indent);
+                }
+
+                for branch in branches.iter() {
+                    writeln!(f, "{}if {}:", pad, branch.state)?;
+                    match branch.plan {
+                        ConditionalPlan::Done => writeln!(f, "{}    goal", pad)?,
+                        _ => branch.plan.write(f, indent + 1)?,
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// prints one action per line and an indented `if` block for every outcome of an action
+impl fmt::Display for ConditionalPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConditionalPlan::Done => writeln!(f, "goal"),
+            _ => self.write(f, 0),
+        }
+    }
+}
+
+/// returns conditional plan that reaches the goal whatever the outcomes are, None if there is none
+pub fn and_or_graph_search(problem: Box<dyn NondeterministicProblem>) -> Option<ConditionalPlan> {
+    or_search(&*problem, &mut vec![])
+}
+
+fn or_search(state: &dyn NondeterministicProblem, path: &mut Vec<u64>) -> Option<ConditionalPlan> {
+    if state.test_goal() {
+        return Some(ConditionalPlan::Done);
+    }
+    if path.contains(&state.hash_code()) {
+        return None;
+    }
+
+    path.push(state.hash_code());
+    let plan = state.actions().into_iter().find_map(|action| {
+        and_search(state.results(&action), path).map(|branches| ConditionalPlan::Step {
+            name: state.action_name(&action),
+            action,
+            branches,
+        })
+    });
+    path.pop();
+
+    plan
+}
+
+fn and_search(
+    states: Vec<Box<dyn NondeterministicProblem>>,
+    path: &mut Vec<u64>,
+) -> Option<Vec<Branch>> {
+    states
+        .iter()
+        .map(|state| {
+            or_search(&**state, path).map(|plan| Branch {
+                state: state.as_string(),
+                plan,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // walks along a line towards position 0; stepping may slip and leave the walker in place
+    // unless the walker is careful, which is only possible at even positions
+    struct SlipperyLine {
+        position: usize,
+    }
+
+    impl NondeterministicProblem for SlipperyLine {
+        fn actions(&self) -> Vec<Action> {
+            if self.position.is_multiple_of(2) {
+                vec![Action(0), Action(1)]
+            } else {
+                vec![Action(0)]
+            }
+        }
+
+        fn results(&self, action: &Action) -> Vec<Box<dyn NondeterministicProblem>> {
+            let step = SlipperyLine {
+                position: self.position - 1,
+            };
+            match action.0 {
+                0 => vec![
+                    Box::new(step),
+                    Box::new(SlipperyLine {
+                        position: self.position,
+                    }),
+                ],
+                _ => vec![Box::new(step)],
+            }
+        }
+
+        fn test_goal(&self) -> bool {
+            self.position == 0
+        }
+
+        fn as_string(&self) -> String {
+            self.position.to_string()
+        }
+
+        fn hash_code(&self) -> u64 {
+            self.position as u64
+        }
+    }
+
+    #[test]
+    fn test_goal_state_needs_no_plan() {
+        let plan = and_or_graph_search(Box::new(SlipperyLine { position: 0 })).unwrap();
+
+        assert_eq!(ConditionalPlan::Done, plan);
+        assert_eq!(0, plan.depth());
+        assert_eq!("goal\n", plan.to_string());
+    }
+
+    #[test]
+    fn test_plan_avoids_outcomes_that_loop() {
+        let plan = and_or_graph_search(Box::new(SlipperyLine { position: 2 }));
+
+        // from 2 the careful step reaches 1, but from 1 a slip can repeat forever
+        assert_eq!(None, plan);
+    }
+
+    #[test]
+    fn test_branch_follows_the_outcome() {
+        struct Fork(usize);
+        impl NondeterministicProblem for Fork {
+            fn actions(&self) -> Vec<Action> {
+                vec![Action(0)]
+            }
+            fn results(&self, _: &Action) -> Vec<Box<dyn NondeterministicProblem>> {
+                vec![Box::new(Fork(self.0 + 1)), Box::new(Fork(self.0 + 2))]
+            }
+            fn test_goal(&self) -> bool {
+                self.0 >= 2
+            }
+            fn as_string(&self) -> String {
+                self.0.to_string()
+            }
+            fn hash_code(&self) -> u64 {
+                self.0 as u64
+            }
+        }
+
+        let plan = and_or_graph_search(Box::new(Fork(0))).unwrap();
+
+        assert_eq!(2, plan.depth());
+        assert_eq!(Some(&ConditionalPlan::Done), plan.branch(&Fork(2)));
+        assert_eq!(1, plan.branch(&Fork(1)).unwrap().depth());
+        assert_eq!(None, plan.branch(&Fork(5)));
+        assert_eq!(
+            "0\nif 1:\n    0\n    if 2:\n        goal\n    if 3:\n        goal\nif 2:\n    goal\n",
+            plan.to_string()
+        );
+    }
+}
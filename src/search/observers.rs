@@ -0,0 +1,351 @@
+//! Observers are notified by the search algorithms about every goal test,
+//! expansion and generated child node; they are used to collect statistics
+//! and traces without touching the algorithms themselves.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::search::SearchNode;
+
+pub trait SearchObserver {
+    fn on_goal_test(&mut self, _node: &SearchNode, _is_goal: bool) {}
+    fn on_expand(&mut self, _node: &SearchNode) {}
+    fn on_generate(&mut self, _parent: &SearchNode, _child: &SearchNode) {}
+    // child was not added into the frontier, because its state was already explored
+    fn on_prune(&mut self, _parent: &SearchNode, _child: &SearchNode) {}
+    // the search gives up and returns no solution as soon as this is true
+    fn should_stop(&self) -> bool {
+        false
+    }
+}
+
+/// Observer that ignores everything; used by the search functions without observer
+pub struct NoopObserver;
+
+impl SearchObserver for NoopObserver {}
+
+/// Notifies both observers, so counting and tracing can be combined
+impl<A: SearchObserver, B: SearchObserver> SearchObserver for (A, B) {
+    fn on_goal_test(&mut self, node: &SearchNode, is_goal: bool) {
+        self.0.on_goal_test(node, is_goal);
+        self.1.on_goal_test(node, is_goal);
+    }
+
+    fn on_expand(&mut self, node: &SearchNode) {
+        self.0.on_expand(node);
+        self.1.on_expand(node);
+    }
+
+    fn on_generate(&mut self, parent: &SearchNode, child: &SearchNode) {
+        self.0.on_generate(parent, child);
+        self.1.on_generate(parent, child);
+    }
+
+    fn on_prune(&mut self, parent: &SearchNode, child: &SearchNode) {
+        self.0.on_prune(parent, child);
+        self.1.on_prune(parent, child);
+    }
+
+    fn should_stop(&self) -> bool {
+        self.0.should_stop() || self.1.should_stop()
+    }
+}
+
+/// Optional observers are notified only when they are present
+impl<T: SearchObserver> SearchObserver for Option<T> {
+    fn on_goal_test(&mut self, node: &SearchNode, is_goal: bool) {
+        if let Some(observer) = self {
+            observer.on_goal_test(node, is_goal);
+        }
+    }
+
+    fn on_expand(&mut self, node: &SearchNode) {
+        if let Some(observer) = self {
+            observer.on_expand(node);
+        }
+    }
+
+    fn on_generate(&mut self, parent: &SearchNode, child: &SearchNode) {
+        if let Some(observer) = self {
+            observer.on_generate(parent, child);
+        }
+    }
+
+    fn on_prune(&mut self, parent: &SearchNode, child: &SearchNode) {
+        if let Some(observer) = self {
+            observer.on_prune(parent, child);
+        }
+    }
+
+    fn should_stop(&self) -> bool {
+        self.as_ref().is_some_and(|observer| observer.should_stop())
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CountingObserver {
+    pub goal_tests: usize,
+    pub expanded: usize,
+    pub generated: usize,
+    pub pruned: usize,
+    pub max_depth: usize,
+}
+
+impl CountingObserver {
+    pub fn new() -> Self {
+        CountingObserver::default()
+    }
+}
+
+impl SearchObserver for CountingObserver {
+    fn on_goal_test(&mut self, _node: &SearchNode, _is_goal: bool) {
+        self.goal_tests += 1;
+    }
+
+    fn on_expand(&mut self, node: &SearchNode) {
+        self.expanded += 1;
+        self.max_depth = self.max_depth.max(node.depth());
+    }
+
+    fn on_generate(&mut self, _parent: &SearchNode, child: &SearchNode) {
+        self.generated += 1;
+        self.max_depth = self.max_depth.max(child.depth());
+    }
+
+    fn on_prune(&mut self, _parent: &SearchNode, _child: &SearchNode) {
+        self.pruned += 1;
+    }
+}
+
+/// Stops the search after the number of expanded nodes or the time runs out
+#[derive(Debug, Clone)]
+pub struct LimitObserver {
+    max_expanded: Option<usize>,
+    deadline: Option<Instant>,
+    expanded: usize,
+}
+
+impl LimitObserver {
+    pub fn new(max_expanded: Option<usize>, timeout: Option<Duration>) -> Self {
+        LimitObserver {
+            max_expanded,
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+            expanded: 0,
+        }
+    }
+}
+
+impl SearchObserver for LimitObserver {
+    fn on_expand(&mut self, _node: &SearchNode) {
+        self.expanded += 1;
+    }
+
+    fn should_stop(&self) -> bool {
+        self.max_expanded.is_some_and(|max| self.expanded >= max)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// Writes every event as a single JSON object per line, e.g.
+/// {"step":3,"event":"expand","depth":1,"path_cost":1,"action":2,"state":"..."}
+/// the first write error stops the tracing and is returned by `finish`
+pub struct JsonLinesObserver<W: Write> {
+    writer: W,
+    step: usize,
+    error: Option<io::Error>,
+}
+
+impl JsonLinesObserver<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::create(path)?;
+        Ok(JsonLinesObserver::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> JsonLinesObserver<W> {
+    pub fn new(writer: W) -> Self {
+        JsonLinesObserver {
+            writer,
+            step: 0,
+            error: None,
+        }
+    }
+
+    /// flushes the trace and returns the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_event(&mut self, event: &str, node: &SearchNode, extra: &str) {
+        if self.error.is_some() {
+            return;
+        }
+
+        let action = match node.action() {
+            Some(action) => action.0.to_string(),
+            None => "null".to_string(),
+        };
+        let res = writeln!(
+            self.writer,
+            "{{\"step\":{},\"event\":\"{}\",\"depth\":{},\"path_cost\":{},\"action\":{},\"state\":{}{}}}",
+            self.step,
+            event,
+            node.depth(),
+            node.path_cost(),
+            action,
+            json_string(&node.item().as_string()),
+            extra
+        );
+
+        self.step += 1;
+        if let Err(err) = res {
+            self.error = Some(err);
+        }
+    }
+}
+
+impl<W: Write> SearchObserver for JsonLinesObserver<W> {
+    fn on_goal_test(&mut self, node: &SearchNode, is_goal: bool) {
+        self.write_event("goal_test", node, &format!(",\"is_goal\":{}", is_goal));
+    }
+
+    fn on_expand(&mut self, node: &SearchNode) {
+        self.write_event("expand", node, "");
+    }
+
+    fn on_generate(&mut self, _parent: &SearchNode, child: &SearchNode) {
+        self.write_event("generate", child, "");
+    }
+
+    fn on_prune(&mut self, _parent: &SearchNode, child: &SearchNode) {
+        self.write_event("prune", child, "");
+    }
+}
+
+/// Prints a progress line into stderr after every N expanded nodes
+pub struct ProgressObserver {
+    every: usize,
+    expanded: usize,
+    generated: usize,
+    max_depth: usize,
+}
+
+impl ProgressObserver {
+    pub fn new(every: usize) -> Self {
+        ProgressObserver {
+            every: every.max(1),
+            expanded: 0,
+            generated: 0,
+            max_depth: 0,
+        }
+    }
+}
+
+impl SearchObserver for ProgressObserver {
+    fn on_expand(&mut self, node: &SearchNode) {
+        self.expanded += 1;
+        self.max_depth = self.max_depth.max(node.depth());
+
+        if self.expanded.is_multiple_of(self.every) {
+            eprintln!(
+                "expanded {} nodes, generated {}, max depth {}",
+                self.expanded, self.generated, self.max_depth
+            );
+        }
+    }
+
+    fn on_generate(&mut self, _parent: &SearchNode, _child: &SearchNode) {
+        self.generated += 1;
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eight_puzzle::EightPuzzle;
+    use crate::search::uninformed::{
+        breadth_first_search_observed, iterative_deepening_search_observed,
+    };
+
+    #[test]
+    fn test_counting_observer_counts_search_events() {
+        let puzzle = Box::new(EightPuzzle::from_row([1, 2, 3, 4, 0, 5, 7, 8, 6]));
+        let mut counter = CountingObserver::new();
+
+        let solution = breadth_first_search_observed(puzzle, &mut counter).unwrap();
+
+        assert_eq!(2, solution.depth());
+        assert_eq!(counter.expanded + 1, counter.goal_tests);
+        assert!(counter.generated >= counter.expanded);
+        assert!(counter.pruned > 0);
+        assert!(counter.max_depth > solution.depth());
+    }
+
+    #[test]
+    fn test_json_lines_observer_writes_one_line_per_event() {
+        let puzzle = Box::new(EightPuzzle::from_row([1, 2, 3, 4, 5, 0, 7, 8, 6]));
+        let mut observer = (CountingObserver::new(), JsonLinesObserver::new(vec![]));
+
+        breadth_first_search_observed(puzzle, &mut observer).unwrap();
+
+        let (counter, tracer) = observer;
+        let trace = String::from_utf8(tracer.finish().unwrap()).unwrap();
+        let lines: Vec<&str> = trace.lines().collect();
+        let total = counter.goal_tests + counter.expanded + counter.generated + counter.pruned;
+
+        assert_eq!(total, lines.len());
+        assert!(lines[0].starts_with("{\"step\":0,\"event\":\"goal_test\",\"depth\":0"));
+        assert!(lines.last().unwrap().contains("\"is_goal\":true"));
+    }
+
+    #[test]
+    fn test_json_string_escapes_special_characters() {
+        assert_eq!("\"a\\\"b\\\\c\\n\"", json_string("a\"b\\c\n"));
+    }
+
+    #[test]
+    fn test_limit_observer_stops_the_search() {
+        let board = [8, 6, 7, 2, 5, 4, 3, 0, 1];
+        let mut observer = (CountingObserver::new(), LimitObserver::new(Some(50), None));
+
+        let solution =
+            breadth_first_search_observed(Box::new(EightPuzzle::from_row(board)), &mut observer);
+
+        assert!(solution.is_none());
+        assert_eq!(50, observer.0.expanded);
+        assert!(iterative_deepening_search_observed(
+            Box::new(EightPuzzle::from_row(board)),
+            &mut LimitObserver::new(None, Some(Duration::from_millis(0)))
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,417 @@
+//! Online search
+//! An online agent does not know the results of its actions in advance: it sees only the
+//! current state, acts in the environment and learns the state space as it moves.
+//! `Environment` simulates the world around any `SearchProblem`, for example `EightPuzzle`,
+//! and logs the trajectory the agent actually walked.
+//! source:
+//! AIMA 3rd edition, chapter 4.5, figures 4.21 and 4.24
+//! https://github.com/aimacode/aima-python/blob/master/search.py
+
+use std::collections::HashMap;
+
+use crate::search::{Action, SearchProblem};
+
+/// what the agent observes in the current state
+#[derive(Debug, Clone, PartialEq)]
+pub struct Percept {
+    /// identifies the state, `SearchProblem::hash_code`
+    pub state: u64,
+    pub description: String,
+    pub actions: Vec<Action>,
+    pub goal: bool,
+    /// heuristic estimate of the cost to the goal
+    pub h: u32,
+    /// cost of the step that led to this state, 0 at the start
+    pub cost: u32,
+}
+
+pub trait OnlineAgent {
+    /// next action in the state, None when the agent stops
+    fn act(&mut self, percept: &Percept) -> Option<Action>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryStep {
+    /// state in which the action was done
+    pub state: String,
+    pub action: Action,
+    pub action_name: String,
+    pub cost: u32,
+}
+
+pub struct Environment {
+    state: Box<dyn SearchProblem>,
+    last_cost: u32,
+    trajectory: Vec<TrajectoryStep>,
+}
+
+impl Environment {
+    pub fn new(state: Box<dyn SearchProblem>) -> Self {
+        Environment {
+            state,
+            last_cost: 0,
+            trajectory: vec![],
+        }
+    }
+
+    pub fn percept(&self) -> Percept {
+        Percept {
+            state: self.state.hash_code(),
+            description: self.state.as_string(),
+            actions: self.state.actions(),
+            goal: self.state.test_goal(),
+            h: self.state.value(),
+            cost: self.last_cost,
+        }
+    }
+
+    pub fn execute(&mut self, action: &Action) -> Result<(), String> {
+        if !self.state.actions().contains(action) {
+            return Err(format!(
+                "action {} is not possible in {}",
+                self.state.action_name(action),
+                self.state.as_string()
+            ));
+        }
+
+        let next = self.state.result(action);
+        self.last_cost = next.path_cost();
+        self.trajectory.push(TrajectoryStep {
+            state: self.state.as_string(),
+            action: *action,
+            action_name: self.state.action_name(action),
+            cost: self.last_cost,
+        });
+        self.state = next;
+
+        Ok(())
+    }
+
+    pub fn state(&self) -> &dyn SearchProblem {
+        &*self.state
+    }
+
+    pub fn trajectory(&self) -> &[TrajectoryStep] {
+        &self.trajectory
+    }
+
+    pub fn cost(&self) -> u32 {
+        self.trajectory.iter().map(|step| step.cost).sum()
+    }
+}
+
+/// lets the agent act until it stops or runs out of steps, returns true if it reached the goal
+pub fn run_online_agent(
+    agent: &mut dyn OnlineAgent,
+    environment: &mut Environment,
+    max_steps: usize,
+) -> Result<bool, String> {
+    for _ in 0..max_steps {
+        match agent.act(&environment.percept()) {
+            Some(action) => environment.execute(&action)?,
+            None => break,
+        }
+    }
+
+    Ok(environment.state().test_goal())
+}
+
+/// Online depth-first search;
+/// it backtracks physically, so every action must have a reverse that undoes it
+pub struct OnlineDfsAgent {
+    reverse: fn(&Action) -> Action,
+    result: HashMap<(u64, usize), u64>,
+    untried: HashMap<u64, Vec<Action>>,
+    // actions that lead back to the states the agent came from, latest last
+    unbacktracked: HashMap<u64, Vec<Action>>,
+    previous: Option<(u64, Action)>,
+}
+
+impl OnlineDfsAgent {
+    pub fn new(reverse: fn(&Action) -> Action) -> Self {
+        OnlineDfsAgent {
+            reverse,
+            result: HashMap::new(),
+            untried: HashMap::new(),
+            unbacktracked: HashMap::new(),
+            previous: None,
+        }
+    }
+
+    /// number of states the agent has been in
+    pub fn visited(&self) -> usize {
+        self.untried.len()
+    }
+}
+
+impl OnlineAgent for OnlineDfsAgent {
+    fn act(&mut self, percept: &Percept) -> Option<Action> {
+        if percept.goal {
+            return None;
+        }
+
+        let state = percept.state;
+        self.untried.entry(state).or_insert_with(|| {
+            let mut actions = percept.actions.clone();
+            // untried actions are taken from the end, so the first action is tried first
+            actions.reverse();
+            actions
+        });
+
+        if let Some((previous, action)) = self.previous {
+            let new_result = self.result.insert((previous, action.0), state).is_none();
+            if new_result && previous != state {
+                self.unbacktracked
+                    .entry(state)
+                    .or_default()
+                    .push((self.reverse)(&action));
+            }
+        }
+
+        let action = match self.untried.get_mut(&state).unwrap().pop() {
+            Some(action) => action,
+            None => self.unbacktracked.get_mut(&state)?.pop()?,
+        };
+        self.previous = Some((state, action));
+
+        Some(action)
+    }
+}
+
+/// Learning real-time A*;
+/// updates the estimated cost of the state it leaves and moves to the neighbor that looks
+/// cheapest, where actions not tried yet look as good as the current estimate
+#[derive(Default)]
+pub struct LrtaStarAgent {
+    h: HashMap<u64, u32>,
+    descriptions: HashMap<u64, String>,
+    actions: HashMap<u64, Vec<Action>>,
+    // learned outcome and step cost of every action tried
+    result: HashMap<(u64, usize), (u64, u32)>,
+    previous: Option<(u64, Action)>,
+}
+
+impl LrtaStarAgent {
+    pub fn new() -> Self {
+        LrtaStarAgent::default()
+    }
+
+    /// learned cost estimate of every state visited, ordered by state description
+    pub fn heuristic_table(&self) -> Vec<(String, u32)> {
+        let mut table: Vec<(String, u32)> = self
+            .h
+            .iter()
+            .map(|(state, &h)| (self.descriptions[state].clone(), h))
+            .collect();
+        table.sort();
+
+        table
+    }
+
+    fn cost(&self, state: u64, action: &Action) -> u32 {
+        match self.result.get(&(state, action.0)) {
+            Some(&(next, step_cost)) => step_cost.saturating_add(self.h[&next]),
+            None => self.h[&state],
+        }
+    }
+
+    fn best_action(&self, state: u64) -> Option<Action> {
+        self.actions[&state]
+            .iter()
+            .min_by_key(|action| self.cost(state, action))
+            .copied()
+    }
+}
+
+impl OnlineAgent for LrtaStarAgent {
+    fn act(&mut self, percept: &Percept) -> Option<Action> {
+        let state = percept.state;
+
+        self.h
+            .entry(state)
+            .or_insert(if percept.goal { 0 } else { percept.h });
+        self.descriptions
+            .entry(state)
+            .or_insert_with(|| percept.description.clone());
+        self.actions
+            .entry(state)
+            .or_insert_with(|| percept.actions.clone());
+
+        if let Some((previous, action)) = self.previous.take() {
+            self.result
+                .insert((previous, action.0), (state, percept.cost));
+            if let Some(best) = self.best_action(previous) {
+                let estimate = self.cost(previous, &best);
+                self.h.insert(previous, estimate);
+            }
+        }
+        if percept.goal {
+            return None;
+        }
+
+        let action = self.best_action(state)?;
+        self.previous = Some((state, action));
+
+        Some(action)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eight_puzzle::{EightPuzzle, Heuristic, TileDirection};
+
+    fn reverse(action: &Action) -> Action {
+        TileDirection::from(action).opposite().into()
+    }
+
+    fn environment(board: [u8; 9]) -> Environment {
+        Environment::new(Box::new(
+            EightPuzzle::from_row(board).with_heuristic(Heuristic::ManhattanDistance),
+        ))
+    }
+
+    #[test]
+    fn test_environment_logs_trajectory() {
+        let mut environment = environment([1, 2, 3, 4, 5, 6, 0, 7, 8]);
+
+        environment.execute(&TileDirection::Right.into()).unwrap();
+        environment.execute(&TileDirection::Right.into()).unwrap();
+
+        assert!(environment.percept().goal);
+        assert_eq!(2, environment.cost());
+        assert_eq!(
+            vec!["Right", "Right"],
+            environment
+                .trajectory()
+                .iter()
+                .map(|step| step.action_name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(environment.execute(&TileDirection::Down.into()).is_err());
+    }
+
+    #[test]
+    fn test_lrta_star_reaches_the_goal() {
+        let mut environment = environment([1, 2, 3, 4, 5, 6, 0, 7, 8]);
+        let mut agent = LrtaStarAgent::new();
+
+        assert_eq!(
+            Ok(true),
+            run_online_agent(&mut agent, &mut environment, 100)
+        );
+        let table = agent.heuristic_table();
+        let goal = environment.state().as_string();
+        assert!(table.contains(&(goal, 0)));
+        // untried actions look as good as the estimate, so the agent explores first
+        assert!(environment.trajectory().len() > 2);
+    }
+
+    #[test]
+    fn test_lrta_star_never_lowers_estimates() {
+        let board = [5, 1, 2, 6, 3, 0, 4, 7, 8];
+        let mut environment = environment(board);
+        let mut agent = LrtaStarAgent::new();
+
+        assert_eq!(
+            Ok(true),
+            run_online_agent(&mut agent, &mut environment, 10_000)
+        );
+
+        // replays the walk to see the estimates the agent started with
+        let mut replay = self::environment(board);
+        let mut initial = vec![(replay.percept().description, replay.percept().h)];
+        for step in environment.trajectory() {
+            replay.execute(&step.action).unwrap();
+            initial.push((replay.percept().description, replay.percept().h));
+        }
+        let table = agent.heuristic_table();
+        let learned = |state: &str| table.iter().find(|(s, _)| s == state).unwrap().1;
+
+        assert!(initial.iter().all(|(state, h)| learned(state) >= *h));
+    }
+
+    // squares 0 to 4 in a row with the goal at 4; the estimate lures the agent into square 0
+    struct Corridor(usize);
+
+    impl SearchProblem for Corridor {
+        fn actions(&self) -> Vec<Action> {
+            match self.0 {
+                0 => vec![Action(1)],
+                _ => vec![Action(0), Action(1)],
+            }
+        }
+
+        fn result(&self, action: &Action) -> Box<dyn SearchProblem> {
+            match action.0 {
+                0 => Box::new(Corridor(self.0 - 1)),
+                _ => Box::new(Corridor(self.0 + 1)),
+            }
+        }
+
+        fn test_goal(&self) -> bool {
+            self.0 == 4
+        }
+
+        fn path_cost(&self) -> u32 {
+            1
+        }
+
+        fn value(&self) -> u32 {
+            [0, 2, 2, 1, 0][self.0]
+        }
+
+        fn as_string(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn hash_code(&self) -> u64 {
+            self.0 as u64
+        }
+    }
+
+    #[test]
+    fn test_lrta_star_learns_its_way_out_of_a_dead_end() {
+        let mut environment = Environment::new(Box::new(Corridor(1)));
+        let mut agent = LrtaStarAgent::new();
+
+        assert_eq!(
+            Ok(true),
+            run_online_agent(&mut agent, &mut environment, 100)
+        );
+        // the dead end looked like the goal, now every estimate is the true distance
+        let learned: Vec<u32> = agent.heuristic_table().iter().map(|(_, h)| *h).collect();
+        assert_eq!(vec![4, 3, 2, 1, 0], learned);
+        assert!(environment.trajectory().len() > 3);
+    }
+
+    #[test]
+    fn test_online_dfs_reaches_the_goal() {
+        let mut environment = environment([1, 2, 3, 4, 5, 6, 7, 0, 8]);
+        let mut agent = OnlineDfsAgent::new(reverse);
+
+        assert_eq!(
+            Ok(true),
+            run_online_agent(&mut agent, &mut environment, 100_000)
+        );
+        assert!(agent.visited() <= environment.trajectory().len());
+    }
+
+    #[test]
+    fn test_online_dfs_backtracks_through_dead_ends() {
+        // only the blank moves, so every state has a reverse step
+        let mut environment = environment([1, 2, 3, 4, 5, 6, 0, 7, 8]);
+        let mut agent = OnlineDfsAgent::new(reverse);
+
+        assert_eq!(
+            Ok(true),
+            run_online_agent(&mut agent, &mut environment, 1_000_000)
+        );
+        let backtracks = environment
+            .trajectory()
+            .windows(2)
+            .filter(|steps| steps[1].action == reverse(&steps[0].action))
+            .count();
+        assert!(backtracks > 0);
+    }
+}
@@ -0,0 +1,493 @@
+//! Step-by-step versions of the uninformed search strategies.
+//! Every strategy is exposed as an iterator of `SearchEvent`s, so callers can
+//! pause the search after any step, inspect it and render what happened;
+//! the traversal order is the same as in `search::uninformed`.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::search::frontiers::{Frontier, PriorityFrontier, QueueFrontier, StackFrontier};
+use crate::search::{SearchNode, SearchProblem};
+
+#[derive(Debug, Clone)]
+pub enum SearchEvent {
+    /// node was taken from the frontier and its children were generated
+    Expanded(SearchNode),
+    /// child node was added into the frontier
+    Generated(SearchNode),
+    /// child node was dropped, because its state was already explored
+    Pruned(SearchNode),
+    /// iterative deepening restarted the search with a new depth limit
+    LimitIncreased(usize),
+    /// node passed the goal test; it is the last event of the search
+    GoalFound(SearchNode),
+    /// bidirectional searches reached the same state, the first node from the problem and
+    /// the second from the goal; it is the last event of the search
+    Met(SearchNode, SearchNode),
+}
+
+/// Graph search over any frontier; it drives depth_first, breadth_first and uniform_cost steps.
+/// The children of an expanded node are added into the frontier right away,
+/// their `Generated` and `Pruned` events follow the `Expanded` event of the parent.
+pub struct FrontierSteps<F: Frontier> {
+    frontier: F,
+    explored: HashSet<u64>,
+    pending: VecDeque<SearchEvent>,
+    finished: bool,
+}
+
+impl<F: Frontier> FrontierSteps<F> {
+    pub fn new(mut frontier: F, problem: Box<dyn SearchProblem>) -> Self {
+        frontier.add(SearchNode::root(problem));
+
+        FrontierSteps {
+            frontier,
+            explored: HashSet::new(),
+            pending: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    pub fn frontier_len(&self) -> usize {
+        self.frontier.len()
+    }
+
+    pub fn explored_len(&self) -> usize {
+        self.explored.len()
+    }
+}
+
+impl<F: Frontier> Iterator for FrontierSteps<F> {
+    type Item = SearchEvent;
+
+    fn next(&mut self) -> Option<SearchEvent> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+
+        if self.finished {
+            return None;
+        }
+
+        let current_node = match self.frontier.remove() {
+            Some(node) => node,
+            None => {
+                self.finished = true;
+                return None;
+            }
+        };
+
+        if current_node.is_goal() {
+            self.finished = true;
+            return Some(SearchEvent::GoalFound(current_node));
+        }
+
+        let child_nodes = current_node.expand();
+        self.explored.insert(current_node.item().hash_code());
+
+        for child_node in child_nodes.into_iter() {
+            if self.explored.contains(&child_node.item().hash_code()) {
+                self.pending.push_back(SearchEvent::Pruned(child_node));
+            } else {
+                self.frontier.add(child_node.clone());
+                self.pending.push_back(SearchEvent::Generated(child_node));
+            }
+        }
+
+        Some(SearchEvent::Expanded(current_node))
+    }
+}
+
+/// Tree search that stops going deeper after `limit` levels;
+/// it visits the nodes in the same order as the recursive implementation.
+pub struct DepthLimitedSteps {
+    stack: Vec<(SearchNode, usize)>,
+    pending: VecDeque<SearchEvent>,
+    cutoff_occurred: bool,
+    finished: bool,
+}
+
+impl DepthLimitedSteps {
+    pub fn new(root_node: SearchNode, limit: usize) -> Self {
+        DepthLimitedSteps {
+            stack: vec![(root_node, limit)],
+            pending: VecDeque::new(),
+            cutoff_occurred: false,
+            finished: false,
+        }
+    }
+
+    /// true if some node was left unexpanded because it hit the depth limit
+    pub fn cutoff_occurred(&self) -> bool {
+        self.cutoff_occurred
+    }
+}
+
+impl Iterator for DepthLimitedSteps {
+    type Item = SearchEvent;
+
+    fn next(&mut self) -> Option<SearchEvent> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+
+        if self.finished {
+            return None;
+        }
+
+        while let Some((node, limit)) = self.stack.pop() {
+            if node.is_goal() {
+                self.finished = true;
+                return Some(SearchEvent::GoalFound(node));
+            }
+
+            if limit == 0 {
+                self.cutoff_occurred = true;
+                continue;
+            }
+
+            let child_nodes = node.expand();
+            // stack is LIFO, so children are pushed in reverse to keep the recursive order
+            for child_node in child_nodes.iter().rev() {
+                self.stack.push((child_node.clone(), limit - 1));
+            }
+            for child_node in child_nodes.into_iter() {
+                self.pending.push_back(SearchEvent::Generated(child_node));
+            }
+
+            return Some(SearchEvent::Expanded(node));
+        }
+
+        self.finished = true;
+        None
+    }
+}
+
+/// Repeats depth limited steps with growing limit until the goal is found;
+/// unlike the recursive version it stops when the whole tree fits under the limit.
+pub struct IterativeDeepeningSteps {
+    root_node: SearchNode,
+    limit: usize,
+    current: DepthLimitedSteps,
+    finished: bool,
+}
+
+impl IterativeDeepeningSteps {
+    pub fn new(root_node: SearchNode) -> Self {
+        let current = DepthLimitedSteps::new(root_node.clone(), 0);
+
+        IterativeDeepeningSteps {
+            root_node,
+            limit: 0,
+            current,
+            finished: false,
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl Iterator for IterativeDeepeningSteps {
+    type Item = SearchEvent;
+
+    fn next(&mut self) -> Option<SearchEvent> {
+        if self.finished {
+            return None;
+        }
+
+        match self.current.next() {
+            Some(SearchEvent::GoalFound(node)) => {
+                self.finished = true;
+                Some(SearchEvent::GoalFound(node))
+            }
+            Some(event) => Some(event),
+            None if self.current.cutoff_occurred() => {
+                self.limit += 1;
+                self.current = DepthLimitedSteps::new(self.root_node.clone(), self.limit);
+                Some(SearchEvent::LimitIncreased(self.limit))
+            }
+            None => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SearchDirection {
+    Forward,
+    Backward,
+}
+
+/// Breadth first steps from both directions, taking turns until the searches meet.
+/// Every direction keeps its own reached states, a state explored by one search
+/// is where the other one meets it.
+pub struct BidirectionalSteps {
+    front_frontier: QueueFrontier,
+    back_frontier: QueueFrontier,
+    // nodes each search has put into its frontier, by their state
+    front_reached: HashMap<u64, SearchNode>,
+    back_reached: HashMap<u64, SearchNode>,
+    goal_node: SearchNode,
+    pending: VecDeque<SearchEvent>,
+    direction: SearchDirection,
+    finished: bool,
+}
+
+impl BidirectionalSteps {
+    pub fn new(problem: Box<dyn SearchProblem>, goal_problem: Box<dyn SearchProblem>) -> Self {
+        let start_node = SearchNode::root(problem);
+        let goal_node = SearchNode::root(goal_problem);
+
+        let mut front_reached = HashMap::new();
+        front_reached.insert(start_node.item().hash_code(), start_node.clone());
+        let mut back_reached = HashMap::new();
+        back_reached.insert(goal_node.item().hash_code(), goal_node.clone());
+
+        let mut front_frontier = QueueFrontier::new();
+        front_frontier.add(start_node);
+
+        let mut back_frontier = QueueFrontier::new();
+        back_frontier.add(goal_node.clone());
+
+        BidirectionalSteps {
+            front_frontier,
+            back_frontier,
+            front_reached,
+            back_reached,
+            goal_node,
+            pending: VecDeque::new(),
+            direction: SearchDirection::Forward,
+            finished: false,
+        }
+    }
+
+    /// direction of the search that takes the next node from its frontier
+    pub fn direction(&self) -> SearchDirection {
+        self.direction
+    }
+
+    fn step(&mut self, direction: SearchDirection) -> Option<SearchEvent> {
+        let (frontier, reached, other_reached) = match direction {
+            SearchDirection::Forward => (
+                &mut self.front_frontier,
+                &mut self.front_reached,
+                &self.back_reached,
+            ),
+            SearchDirection::Backward => (
+                &mut self.back_frontier,
+                &mut self.back_reached,
+                &self.front_reached,
+            ),
+        };
+
+        let current_node = frontier.remove()?;
+        let other_node = if direction == SearchDirection::Forward && current_node.is_goal() {
+            Some(self.goal_node.clone())
+        } else {
+            other_reached.get(&current_node.item().hash_code()).cloned()
+        };
+        if let Some(other_node) = other_node {
+            self.finished = true;
+            return Some(match direction {
+                SearchDirection::Forward => SearchEvent::Met(current_node, other_node),
+                SearchDirection::Backward => SearchEvent::Met(other_node, current_node),
+            });
+        }
+
+        let child_nodes = current_node.expand();
+        for child_node in child_nodes.into_iter() {
+            match reached.entry(child_node.item().hash_code()) {
+                Entry::Occupied(_) => self.pending.push_back(SearchEvent::Pruned(child_node)),
+                Entry::Vacant(entry) => {
+                    entry.insert(child_node.clone());
+                    frontier.add(child_node.clone());
+                    self.pending.push_back(SearchEvent::Generated(child_node));
+                }
+            }
+        }
+
+        Some(SearchEvent::Expanded(current_node))
+    }
+}
+
+impl Iterator for BidirectionalSteps {
+    type Item = SearchEvent;
+
+    fn next(&mut self) -> Option<SearchEvent> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+
+        while !self.finished {
+            let direction = self.direction;
+            self.direction = match direction {
+                SearchDirection::Forward => {
+                    if self.front_frontier.is_empty() || self.back_frontier.is_empty() {
+                        self.finished = true;
+                        return None;
+                    }
+                    SearchDirection::Backward
+                }
+                SearchDirection::Backward => SearchDirection::Forward,
+            };
+
+            if let Some(event) = self.step(direction) {
+                return Some(event);
+            }
+        }
+
+        None
+    }
+}
+
+pub fn depth_first_steps(problem: Box<dyn SearchProblem>) -> FrontierSteps<StackFrontier> {
+    FrontierSteps::new(StackFrontier::new(), problem)
+}
+
+pub fn breadth_first_steps(problem: Box<dyn SearchProblem>) -> FrontierSteps<QueueFrontier> {
+    FrontierSteps::new(QueueFrontier::new(), problem)
+}
+
+pub fn uniform_cost_steps(problem: Box<dyn SearchProblem>) -> FrontierSteps<PriorityFrontier> {
+    FrontierSteps::new(PriorityFrontier::new(), problem)
+}
+
+pub fn depth_limited_steps(problem: Box<dyn SearchProblem>, limit: usize) -> DepthLimitedSteps {
+    DepthLimitedSteps::new(SearchNode::root(problem), limit)
+}
+
+pub fn iterative_deepening_steps(problem: Box<dyn SearchProblem>) -> IterativeDeepeningSteps {
+    IterativeDeepeningSteps::new(SearchNode::root(problem))
+}
+
+pub fn bidirectional_steps(
+    problem: Box<dyn SearchProblem>,
+    goal_problem: Box<dyn SearchProblem>,
+) -> BidirectionalSteps {
+    BidirectionalSteps::new(problem, goal_problem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eight_puzzle::{EightPuzzle, EightPuzzleState, TileDirection, DEFAULT_GOAL};
+    use crate::search::uninformed::breadth_first_search;
+
+    fn make_puzzle(row: [u8; 9]) -> Box<dyn SearchProblem> {
+        Box::new(EightPuzzle::from_row(row))
+    }
+
+    fn goal_node(events: impl Iterator<Item = SearchEvent>) -> Option<SearchNode> {
+        events.last().and_then(|event| match event {
+            SearchEvent::GoalFound(node) => Some(node),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_frontier_steps_finds_goal_at_root() {
+        let mut steps = breadth_first_steps(make_puzzle(DEFAULT_GOAL));
+
+        assert!(matches!(steps.next(), Some(SearchEvent::GoalFound(_))));
+        assert!(steps.next().is_none());
+    }
+
+    #[test]
+    fn test_frontier_steps_expands_root_first() {
+        let mut steps = breadth_first_steps(make_puzzle([1, 2, 3, 4, 0, 5, 7, 8, 6]));
+
+        match steps.next() {
+            Some(SearchEvent::Expanded(node)) => assert_eq!(0, node.depth()),
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert_eq!(4, steps.frontier_len());
+        assert_eq!(1, steps.explored_len());
+    }
+
+    #[test]
+    fn test_frontier_steps_prunes_explored_states() {
+        let steps = breadth_first_steps(make_puzzle([1, 2, 3, 4, 0, 5, 7, 8, 6]));
+
+        assert!(steps
+            .take_while(|event| !matches!(event, SearchEvent::GoalFound(_)))
+            .any(|event| matches!(event, SearchEvent::Pruned(_))));
+    }
+
+    #[test]
+    fn test_breadth_first_steps_match_breadth_first_search() {
+        let row = [1, 2, 3, 7, 4, 5, 0, 8, 6];
+        let expected = breadth_first_search(make_puzzle(row)).unwrap();
+        let node = goal_node(breadth_first_steps(make_puzzle(row))).unwrap();
+
+        assert_eq!(expected.solution(), node.solution());
+    }
+
+    #[test]
+    fn test_depth_limited_steps_stop_after_limit() {
+        let steps = depth_limited_steps(make_puzzle([1, 2, 3, 7, 4, 5, 0, 8, 6]), 2);
+
+        assert!(goal_node(steps).is_none());
+    }
+
+    #[test]
+    fn test_iterative_deepening_steps_increase_limit() {
+        let events: Vec<SearchEvent> =
+            iterative_deepening_steps(make_puzzle([1, 2, 3, 4, 0, 5, 7, 8, 6])).collect();
+
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, SearchEvent::LimitIncreased(2))));
+        assert_eq!(2, goal_node(events.into_iter()).unwrap().depth());
+    }
+
+    #[test]
+    fn test_bidirectional_steps_alternate_directions() {
+        let puzzle = EightPuzzle::from_row([1, 2, 3, 7, 4, 5, 0, 8, 6]);
+        let goal_problem = Box::new(puzzle.reverse_problem());
+        let mut steps = bidirectional_steps(Box::new(puzzle), goal_problem);
+
+        assert_eq!(SearchDirection::Forward, steps.direction());
+        steps.next();
+        assert_eq!(SearchDirection::Backward, steps.direction());
+        assert!(matches!(steps.last(), Some(SearchEvent::Met(_, _))));
+    }
+
+    #[test]
+    fn test_bidirectional_steps_meet_on_the_whole_path() {
+        for (row, length) in [
+            ([1, 2, 3, 4, 5, 0, 7, 8, 6], 1),
+            ([1, 2, 3, 7, 4, 5, 0, 8, 6], 4),
+            ([8, 6, 7, 2, 5, 4, 3, 0, 1], 31),
+        ] {
+            let puzzle = EightPuzzle::from_row(row);
+            let goal_problem = Box::new(puzzle.reverse_problem());
+            let (front, back) = match bidirectional_steps(Box::new(puzzle), goal_problem).last() {
+                Some(SearchEvent::Met(front, back)) => (front, back),
+                other => panic!("unexpected event {:?}", other),
+            };
+            assert_eq!(front.item().hash_code(), back.item().hash_code());
+
+            // the path from the goal is walked back with every move undone
+            let mut moves: Vec<TileDirection> = front.solution()[1..]
+                .iter()
+                .map(TileDirection::from)
+                .collect();
+            moves.extend(
+                back.solution()[1..]
+                    .iter()
+                    .rev()
+                    .map(|action| TileDirection::from(action).opposite()),
+            );
+            let state = moves
+                .iter()
+                .fold(EightPuzzleState::new(row), |state, m| state.next_state(m));
+
+            assert_eq!(&DEFAULT_GOAL, state.value());
+            assert_eq!(length, moves.len());
+        }
+    }
+}
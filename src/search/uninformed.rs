@@ -1,30 +1,48 @@
+//! Uniform search strategies
+//! This module includes a collection of algorithms that systematically tried to find a solution;
+//! methods are originally implemented here:
+//! https://github.com/aimacode/aima-python/blob/master/search.py
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
 use crate::search::frontiers::{Frontier, PriorityFrontier, QueueFrontier, StackFrontier};
+use crate::search::observers::{NoopObserver, SearchObserver};
 use crate::search::{SearchNode, SearchProblem};
 
-/// Uniform search strategies
-/// This module includes a collection of algorithms that systematically tried to find a solution;
-/// methods are originally implemented here:
-/// https://github.com/aimacode/aima-python/blob/master/search.py
-
 /// depth_first_tree_search
 /// it follows first path until it hits wall, then backs up and check other branches;
 /// TODO: fix infinite loop;
 pub fn depth_first_search(problem: Box<dyn SearchProblem>) -> Option<SearchNode> {
+    depth_first_search_observed(problem, &mut NoopObserver)
+}
+
+pub fn depth_first_search_observed(
+    problem: Box<dyn SearchProblem>,
+    observer: &mut dyn SearchObserver,
+) -> Option<SearchNode> {
     let root_node = SearchNode::root(problem);
     let mut frontier = StackFrontier::new();
 
     frontier.add(root_node);
-    traverse_frontier(&mut frontier)
+    traverse_frontier(&mut frontier, observer)
 }
 
 /// Search the shallowest nodes in the search tree first.
 /// Search through the successors of a problem to find a goal.
 pub fn breadth_first_search(problem: Box<dyn SearchProblem>) -> Option<SearchNode> {
+    breadth_first_search_observed(problem, &mut NoopObserver)
+}
+
+pub fn breadth_first_search_observed(
+    problem: Box<dyn SearchProblem>,
+    observer: &mut dyn SearchObserver,
+) -> Option<SearchNode> {
     let root_node = SearchNode::root(problem);
     let mut frontier = QueueFrontier::new();
 
     frontier.add(root_node);
-    traverse_frontier(&mut frontier)
+    traverse_frontier(&mut frontier, observer)
 }
 
 /// search the node by expanding the node n with the lowest path cost g(n).
@@ -38,21 +56,38 @@ pub fn breadth_first_search(problem: Box<dyn SearchProblem>) -> Option<SearchNod
 /// node; we had to convert heap to list, then remove the item and then
 /// build a new node, which was bigger effort than just adding new element
 pub fn uniform_cost_search(problem: Box<dyn SearchProblem>) -> Option<SearchNode> {
+    uniform_cost_search_observed(problem, &mut NoopObserver)
+}
+
+pub fn uniform_cost_search_observed(
+    problem: Box<dyn SearchProblem>,
+    observer: &mut dyn SearchObserver,
+) -> Option<SearchNode> {
     let root_node = SearchNode::root(problem);
     let mut frontier = PriorityFrontier::new();
 
     frontier.add(root_node);
-    traverse_frontier(&mut frontier)
+    traverse_frontier(&mut frontier, observer)
 }
 
 /// Iterative deepening search is a general strategy often used in combination with DFS,
 /// that finds the best depth limit. It does this by gradually increasing the limit
 /// until the goal is found
 pub fn iterative_deepening_search(problem: Box<dyn SearchProblem>) -> Option<SearchNode> {
+    iterative_deepening_search_observed(problem, &mut NoopObserver)
+}
+
+pub fn iterative_deepening_search_observed(
+    problem: Box<dyn SearchProblem>,
+    observer: &mut dyn SearchObserver,
+) -> Option<SearchNode> {
     let root_node = SearchNode::root(problem);
 
     for limit in 0..usize::MAX {
-        if let Some(res) = recursive_dls(&root_node, limit) {
+        if observer.should_stop() {
+            break;
+        }
+        if let Some(res) = recursive_dls(&root_node, limit, observer) {
             return Some(res);
         }
     }
@@ -63,22 +98,42 @@ pub fn iterative_deepening_search(problem: Box<dyn SearchProblem>) -> Option<Sea
 /// This algorithm works around DFS issue of infinite-path problem
 /// by cut-offing the search after reaching to the specified depth;
 pub fn depth_limited_search(problem: Box<dyn SearchProblem>, limit: usize) -> Option<SearchNode> {
+    depth_limited_search_observed(problem, limit, &mut NoopObserver)
+}
+
+pub fn depth_limited_search_observed(
+    problem: Box<dyn SearchProblem>,
+    limit: usize,
+    observer: &mut dyn SearchObserver,
+) -> Option<SearchNode> {
     let root_node = SearchNode::root(problem);
 
-    recursive_dls(&root_node, limit)
+    recursive_dls(&root_node, limit, observer)
 }
 
-fn recursive_dls(node: &SearchNode, limit: usize) -> Option<SearchNode> {
-    if node.item().test_goal() == true {
+fn recursive_dls(
+    node: &SearchNode,
+    limit: usize,
+    observer: &mut dyn SearchObserver,
+) -> Option<SearchNode> {
+    let is_goal = node.item().test_goal();
+    observer.on_goal_test(node, is_goal);
+    if is_goal {
         return Some(node.clone());
     }
 
-    if limit == 0 {
+    if limit == 0 || observer.should_stop() {
         return None;
     }
 
-    for child in node.expand().iter() {
-        if let Some(res) = recursive_dls(&child, limit - 1) {
+    let child_nodes = node.expand();
+    observer.on_expand(node);
+    for child in child_nodes.iter() {
+        observer.on_generate(node, child);
+    }
+
+    for child in child_nodes.iter() {
+        if let Some(res) = recursive_dls(child, limit - 1, observer) {
             return Some(res);
         }
     }
@@ -86,101 +141,129 @@ fn recursive_dls(node: &SearchNode, limit: usize) -> Option<SearchNode> {
     None
 }
 
-/// BreadthFirst from both direction until searches meet
+/// BreadthFirst from both direction until searches meet;
+/// returns the state where they met, as reached from the problem and as reached from the goal.
+/// Only the problem knows how to undo an action, so the caller links the 2 paths together.
 /// source:
 /// http://planning.cs.uiuc.edu/node50.html
 pub fn bidirectional_search(
     problem: Box<dyn SearchProblem>,
     goal_problem: Box<dyn SearchProblem>,
-) -> Option<SearchNode> {
+) -> Option<(SearchNode, SearchNode)> {
+    bidirectional_search_observed(problem, goal_problem, &mut NoopObserver)
+}
+
+pub fn bidirectional_search_observed(
+    problem: Box<dyn SearchProblem>,
+    goal_problem: Box<dyn SearchProblem>,
+    observer: &mut dyn SearchObserver,
+) -> Option<(SearchNode, SearchNode)> {
     let start_node = SearchNode::root(problem);
     let goal_node = SearchNode::root(goal_problem);
 
+    // nodes each search has put into its frontier, by their state
+    let mut front_reached: HashMap<u64, SearchNode> = HashMap::new();
+    front_reached.insert(start_node.item().hash_code(), start_node.clone());
+    let mut back_reached: HashMap<u64, SearchNode> = HashMap::new();
+    back_reached.insert(goal_node.item().hash_code(), goal_node.clone());
+
     let mut front_frontier = QueueFrontier::new();
     front_frontier.add(start_node);
 
     let mut back_frontier = QueueFrontier::new();
-    back_frontier.add(goal_node);
-
-    let mut explored: Vec<SearchNode> = vec![];
+    back_frontier.add(goal_node.clone());
 
-    while !front_frontier.is_empty() && !back_frontier.is_empty() {
+    while !front_frontier.is_empty() && !back_frontier.is_empty() && !observer.should_stop() {
         // consume front_frontier
-        if !front_frontier.is_empty() {
-            if let Some(current_node) = front_frontier.remove() {
-                if current_node.is_goal() {
-                    return Some(current_node);
-                } else if back_frontier.contains(&current_node) {
-                    // TODO: link 2 paths together
-                    return Some(current_node.clone());
-                } else {
-                    let child_nodes = current_node.expand();
-                    explored.push(current_node);
-
-                    // fill frontier
-                    for child_node in child_nodes.into_iter() {
-                        if !explored.contains(&child_node) {
-                            front_frontier.add(child_node);
-                        }
-                    }
-                }
+        if let Some(current_node) = front_frontier.remove() {
+            let is_goal = current_node.is_goal();
+            observer.on_goal_test(&current_node, is_goal);
+
+            if is_goal {
+                return Some((current_node, goal_node));
+            }
+            if let Some(back_node) = back_reached.get(&current_node.item().hash_code()) {
+                return Some((current_node, back_node.clone()));
             }
+            expand_into(
+                &current_node,
+                &mut front_frontier,
+                &mut front_reached,
+                observer,
+            );
         }
 
         // consume back_frontier
-        if !back_frontier.is_empty() {
-            if let Some(current_node) = back_frontier.remove() {
-                if front_frontier.contains(&current_node) {
-                    // TODO: link 2 paths together
-                    return Some(current_node);
-                } else {
-                    let child_nodes = current_node.expand();
-                    explored.push(current_node);
-
-                    // fill frontier
-                    for child_node in child_nodes.into_iter() {
-                        if !explored.contains(&child_node) {
-                            back_frontier.add(child_node);
-                        }
-                    }
-                }
+        if let Some(current_node) = back_frontier.remove() {
+            if let Some(front_node) = front_reached.get(&current_node.item().hash_code()) {
+                return Some((front_node.clone(), current_node));
             }
+            expand_into(
+                &current_node,
+                &mut back_frontier,
+                &mut back_reached,
+                observer,
+            );
         }
     }
 
     None
 }
 
+// adds the children of the node not reached yet into the frontier
+fn expand_into(
+    node: &SearchNode,
+    frontier: &mut impl Frontier,
+    reached: &mut HashMap<u64, SearchNode>,
+    observer: &mut dyn SearchObserver,
+) {
+    let child_nodes = node.expand();
+    observer.on_expand(node);
+
+    for child_node in child_nodes.into_iter() {
+        match reached.entry(child_node.item().hash_code()) {
+            Entry::Occupied(_) => observer.on_prune(node, &child_node),
+            Entry::Vacant(entry) => {
+                observer.on_generate(node, &child_node);
+                entry.insert(child_node.clone());
+                frontier.add(child_node);
+            }
+        }
+    }
+}
+
 // utils ----
 
-fn traverse_frontier(frontier: &mut impl Frontier) -> Option<SearchNode> {
-    let mut explored: Vec<SearchNode> = vec![];
+pub(crate) fn traverse_frontier(
+    frontier: &mut impl Frontier,
+    observer: &mut dyn SearchObserver,
+) -> Option<SearchNode> {
+    let mut explored: HashSet<u64> = HashSet::new();
 
     while let Some(current_node) = frontier.remove() {
-        //debug_search_node(&current_node);
+        let is_goal = current_node.item().test_goal();
+        observer.on_goal_test(&current_node, is_goal);
 
-        if current_node.item().test_goal() {
+        if is_goal {
             return Some(current_node.clone());
         }
+        if observer.should_stop() {
+            return None;
+        }
 
         let child_nodes = current_node.expand();
-        explored.push(current_node);
+        explored.insert(current_node.item().hash_code());
+        observer.on_expand(&current_node);
 
         for child_node in child_nodes.into_iter() {
-            if !explored.contains(&child_node) {
+            if !explored.contains(&child_node.item().hash_code()) {
+                observer.on_generate(&current_node, &child_node);
                 frontier.add(child_node)
+            } else {
+                observer.on_prune(&current_node, &child_node);
             }
         }
     }
 
     None
 }
-
-fn debug_search_node(current_node: &SearchNode) {
-    println!(
-        "step.{:?} {:?} - {:?}",
-        current_node.depth(),
-        current_node.action(),
-        current_node.item()
-    );
-}